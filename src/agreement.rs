@@ -0,0 +1,90 @@
+use crate::{DuplicateKeyPolicy, JsonValue, ParseError, Parser};
+use core::fmt::Debug;
+
+/// Documents covering every kind of value, the parser options and the
+/// errors, valid or not under each of [`parsers`]
+const CORPUS: &[&str] = &[
+    r#"{"a": [1, -2.5e3, true, false, null], "b": {"c": "dé\n"}}"#,
+    r#"{"a": 1, "a": 2, "a": [3]}"#,
+    "\u{FEFF} [\"tab\\there\", \"\"] ",
+    "[1, 2",
+    "[1, 2,]",
+    "{\"a\" 1}",
+    "[{\"unterminated",
+    "\"bad \\x escape\"",
+    "\"control \u{1} character\"",
+    "[01]",
+    "[0.5, 0.1, 1.50]",
+    "[1.]",
+    "[tru]",
+    "[] x",
+    "",
+    "   ",
+    "[[[[]]]]",
+    r#"{key: 1, "quoted": {k2: 2}}"#,
+    r#"{'a"b': ["c'd", 'e\'f', 'g']}"#,
+    r#"["\x", "ok\q\n"]"#,
+    "[1, /* comment */ 2] // end",
+    r#"{1: {"a": 2}, true: null}"#,
+    r#"["\uD83D\uDE00", "\uDE00\uD83D", "😀"]"#,
+    "[1,\u{c}2]",
+    "[1e3, 1e-4]",
+    "[NaN, -Infinity]",
+    r#"{"a": {"x": 1, "z": {"p": 1}}, "b": 2, "a": {"y": 2, "z": {"q": 2}}, "a": {"x": 3}}"#,
+    r#"  ["esc\"aped\n", "é😀", "é😀 mixed ünïcödé"]  "#,
+    r#"{"é": 01}"#,
+    r#""é😀 unterminated"#,
+    "[\"é\u{1}\"]",
+    "😀",
+    "[\"😀\" x]",
+    r#""\u12G4""#,
+    "true false",
+    "nul",
+    "-",
+    "1.e5",
+];
+
+/// Parsers with every option of [`Parser`] turned on in one of them, except
+/// for [`ParseOptions::allow_non_string_keys`](crate::ParseOptions::allow_non_string_keys),
+/// which only some of the ways of parsing support
+pub(crate) fn parsers() -> Vec<Parser> {
+    vec![
+        Parser::new(),
+        Parser::new()
+            .allow_duplicate_keys(false)
+            .max_string_length(3),
+        Parser::new().allow_unquoted_keys(true).max_depth(1),
+        Parser::new()
+            .allow_single_quotes(true)
+            .allow_trailing_commas(true),
+        Parser::new().allow_comments(true).max_elements(4),
+        Parser::new().lenient_escapes(true).raw_numbers(true),
+        Parser::new().preserve_number_text(true),
+        Parser::new().reject_imprecise_numbers(true),
+        Parser::new().replace_invalid_surrogates(true),
+        Parser::new().whitespace(&[' ', '\u{c}']),
+        Parser::new().max_exponent(3),
+        Parser::new().allow_non_finite_numbers(true),
+        Parser::new().duplicate_key_policy(DuplicateKeyPolicy::Merge),
+    ]
+}
+
+/// Checks that `parse` gives the same result as [`Parser::parse`] for every
+/// document in [`CORPUS`] with every one of `parsers`, after `expected` turns
+/// the parsed value into what `parse` returns. Results are compared by their
+/// `Debug` output, so number text that `PartialEq` ignores has to match too.
+pub(crate) fn assert_agrees_with_parse<T: Debug>(
+    parsers: &[Parser],
+    parse: impl Fn(&Parser, &str) -> Result<T, ParseError>,
+    expected: impl Fn(JsonValue) -> T,
+) {
+    for parser in parsers {
+        for input in CORPUS {
+            assert_eq!(
+                format!("{:?}", parse(parser, input)),
+                format!("{:?}", parser.parse(input).map(&expected)),
+                "{input:?} with {parser:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use crate::members::{insert_member, KeyIndex, Member};
+use crate::{
+    check_run_length, expect_key_start, is_unescaped_string_char, next_key_is_unquoted,
+    parse_array_with, parse_identifier, parse_number_token, parse_object_with,
+    unexpected_value_start, DuplicateKeyPolicy, JsonArray, JsonBool, JsonNull, JsonNumber,
+    JsonObject, JsonRawNumber, JsonString, JsonType, JsonValue, ParseError, Parser,
+    WhitespaceSkippingIndexTrackingIter,
+};
+use alloc::borrow::Cow;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+/// A [`JsonValue`] whose strings and keys borrow from the input where they
+/// can. A string without escapes is a slice of the input, only strings that
+/// contain escapes are unescaped into their own allocation.
+///
+/// Objects keep the same semantics as [`JsonObject`]: insertion order, and a
+/// repeated key replaces the earlier value in place. Keys are always strings,
+/// [`ParseOptions::allow_non_string_keys`](crate::ParseOptions::allow_non_string_keys)
+/// isn't supported.
+#[derive(Debug, PartialEq)]
+pub enum JsonValueBorrowed<'a> {
+    Object(Vec<(Cow<'a, str>, JsonValueBorrowed<'a>)>),
+    Array(Vec<JsonValueBorrowed<'a>>),
+    String(Cow<'a, str>),
+    Number(JsonNumber),
+    /// Only produced with [`ParseOptions::raw_numbers`](crate::ParseOptions::raw_numbers),
+    /// always borrowed
+    RawNumber(&'a str),
+    Bool(JsonBool),
+    Null(JsonNull),
+}
+
+impl JsonValueBorrowed<'_> {
+    /// Copies every borrowed string, detaching the value from the input
+    pub fn into_owned(self) -> JsonValue {
+        match self {
+            // Parsing already made the keys unique
+            JsonValueBorrowed::Object(members) => JsonValue::Object(JsonObject(
+                members
+                    .into_iter()
+                    .map(|(key, value)| (JsonString(key.into_owned()), value.into_owned()))
+                    .collect(),
+            )),
+            JsonValueBorrowed::Array(elements) => JsonValue::Array(JsonArray(
+                elements.into_iter().map(Self::into_owned).collect(),
+            )),
+            JsonValueBorrowed::String(s) => JsonValue::String(JsonString(s.into_owned())),
+            JsonValueBorrowed::Number(n) => JsonValue::Number(n),
+            JsonValueBorrowed::RawNumber(n) => JsonValue::RawNumber(JsonRawNumber(n.to_string())),
+            JsonValueBorrowed::Bool(b) => JsonValue::Bool(b),
+            JsonValueBorrowed::Null(n) => JsonValue::Null(n),
+        }
+    }
+}
+
+type Iter<'a> = WhitespaceSkippingIndexTrackingIter<core::str::Chars<'a>>;
+
+impl Parser {
+    /// Same as [`Parser::parse`], but strings and keys borrow from `input`
+    /// unless they contain escapes
+    pub fn parse_borrowed<'a>(&self, input: &'a str) -> Result<JsonValueBorrowed<'a>, ParseError> {
+        self.parse_document_with(input.chars(), |i| parse_value(i, input))
+    }
+}
+
+/// Parses a complete JSON document with the default [`Parser`], borrowing
+/// strings from `input` where possible
+pub fn parse_borrowed(input: &str) -> Result<JsonValueBorrowed<'_>, ParseError> {
+    Parser::new().parse_borrowed(input)
+}
+
+fn parse_value<'a>(i: &mut Iter<'a>, input: &'a str) -> Result<JsonValueBorrowed<'a>, ParseError> {
+    let next_char = i
+        .peek_non_whitespace()?
+        .ok_or(ParseError::UnexpectedEndOfString)?;
+    i.count_element()?;
+    match next_char {
+        '{' => parse_object(i, input).map(JsonValueBorrowed::Object),
+        '[' => parse_array_with(i, |i| parse_value(i, input)).map(JsonValueBorrowed::Array),
+        '"' => parse_string(i, input).map(JsonValueBorrowed::String),
+        '\'' if i.options.allow_single_quotes => {
+            parse_string(i, input).map(JsonValueBorrowed::String)
+        }
+        c if i.options.starts_number(c) && i.options.raw_numbers => {
+            let mut text = i.take_scratch();
+            parse_number_token(i, &mut text)?;
+            let start = i.next_byte_index - text.len();
+            i.return_scratch(text);
+            Ok(JsonValueBorrowed::RawNumber(
+                &input[start..i.next_byte_index],
+            ))
+        }
+        c if i.options.starts_number(c) => Ok(JsonValueBorrowed::Number(JsonNumber::parse(i)?)),
+        't' | 'f' => Ok(JsonValueBorrowed::Bool(JsonBool::parse(i)?)),
+        'n' => Ok(JsonValueBorrowed::Null(JsonNull::parse(i)?)),
+        _ => Err(unexpected_value_start(i)),
+    }
+}
+
+fn parse_object<'a>(
+    i: &mut Iter<'a>,
+    input: &'a str,
+) -> Result<Vec<(Cow<'a, str>, JsonValueBorrowed<'a>)>, ParseError> {
+    let mut members: Vec<(Cow<'a, str>, JsonValueBorrowed<'a>)> = Vec::new();
+    let mut index = KeyIndex::default();
+    parse_object_with(i, |i| {
+        let key_char_index = i.next_char_index();
+        let key_byte_index = i.next_byte_index;
+        let key = parse_key(i, input)?;
+        let policy = i.options.duplicate_key_policy;
+        if policy == DuplicateKeyPolicy::Error && index.position(&members, &key).is_some() {
+            return Err(ParseError::DuplicateKey {
+                key: key.into_owned(),
+                char_index: key_char_index,
+                byte_index: key_byte_index,
+            });
+        }
+        i.expect_specific_char_ignore_whitespace(':')?;
+        let value = parse_value(i, input)?;
+        insert_member(&mut members, &mut index, (key, value), policy);
+        Ok(())
+    })?;
+    Ok(members)
+}
+
+impl<'a> Member for (Cow<'a, str>, JsonValueBorrowed<'a>) {
+    type Key = str;
+
+    fn key(&self) -> &str {
+        &self.0
+    }
+
+    fn object_members(&mut self) -> Option<&mut Vec<Self>> {
+        match &mut self.1 {
+            JsonValueBorrowed::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+}
+
+/// Unquoted keys never contain escapes, so they're always borrowed
+fn parse_key<'a>(i: &mut Iter<'a>, input: &'a str) -> Result<Cow<'a, str>, ParseError> {
+    expect_key_start(i)?;
+    if next_key_is_unquoted(i)? {
+        let start = i.next_byte_index;
+        parse_identifier(i, |_| {})?;
+        return Ok(Cow::Borrowed(&input[start..i.next_byte_index]));
+    }
+    parse_string(i, input)
+}
+
+/// Borrows the run of unescaped characters after the opening quote. If it
+/// doesn't end at the closing quote, the run is copied and parsing carries on
+/// the same way [`JsonString`] does.
+fn parse_string<'a>(i: &mut Iter<'a>, input: &'a str) -> Result<Cow<'a, str>, ParseError> {
+    let quote = i.expect_opening_quote()?;
+    let run_char_index = i.next_char_index();
+    let run_byte_index = i.next_byte_index;
+    let run_len = i
+        .take_str_while(|c| is_unescaped_string_char(c, quote.character))
+        .map_or(0, str::len);
+    let run = &input[run_byte_index..run_byte_index + run_len];
+    check_run_length(
+        i.options.max_string_length,
+        0,
+        run,
+        run_char_index,
+        run_byte_index,
+    )?;
+    if i.next_if(|c| *c == quote.character).is_some() {
+        return Ok(Cow::Borrowed(run));
+    }
+    let mut string = run.to_string();
+    JsonString::parse_rest(i, &mut string, quote)?;
+    Ok(Cow::Owned(string))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agreement::{assert_agrees_with_parse, parsers};
+
+    #[test]
+    fn plain_strings_borrow_from_the_input() {
+        let input = r#"{"key": ["plain", "esc\"aped"]}"#;
+        let Ok(JsonValueBorrowed::Object(members)) = parse_borrowed(input) else {
+            panic!("expected an object");
+        };
+        let (key, value) = &members[0];
+        let Cow::Borrowed(key) = key else {
+            panic!("key should be borrowed");
+        };
+        assert_eq!(key.as_ptr(), input[2..].as_ptr());
+
+        let JsonValueBorrowed::Array(elements) = value else {
+            panic!("expected an array");
+        };
+        let JsonValueBorrowed::String(Cow::Borrowed(plain)) = &elements[0] else {
+            panic!("plain string should be borrowed");
+        };
+        assert_eq!(plain.as_ptr(), input[10..].as_ptr());
+        assert_eq!(*plain, "plain");
+        let JsonValueBorrowed::String(Cow::Owned(escaped)) = &elements[1] else {
+            panic!("escaped string should be owned");
+        };
+        assert_eq!(escaped, "esc\"aped");
+    }
+
+    #[test]
+    fn agrees_with_owned_parse() {
+        assert_agrees_with_parse(
+            &parsers(),
+            |parser, input| {
+                parser
+                    .parse_borrowed(input)
+                    .map(JsonValueBorrowed::into_owned)
+            },
+            |value| value,
+        );
+    }
+}
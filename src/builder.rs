@@ -0,0 +1,81 @@
+use crate::{JsonArray, JsonObject, JsonString, JsonValue};
+use alloc::string::String;
+
+/// Builds a [`JsonObject`] one member at a time, from [`JsonObject::builder`]
+#[derive(Debug, Default)]
+pub struct JsonObjectBuilder(JsonObject);
+
+impl JsonObjectBuilder {
+    /// Members keep the order they're inserted in. Inserting a key again
+    /// replaces its value in place, like [`JsonObject::insert`].
+    pub fn insert(mut self, key: impl Into<String>, value: impl Into<JsonValue>) -> Self {
+        self.0.insert(JsonString(key.into()), value.into());
+        self
+    }
+
+    pub fn build(self) -> JsonObject {
+        self.0
+    }
+}
+
+/// Builds a [`JsonArray`] one element at a time, from [`JsonArray::builder`]
+#[derive(Debug, Default)]
+pub struct JsonArrayBuilder(JsonArray);
+
+impl JsonArrayBuilder {
+    pub fn push(mut self, value: impl Into<JsonValue>) -> Self {
+        self.0 .0.push(value.into());
+        self
+    }
+
+    pub fn build(self) -> JsonArray {
+        self.0
+    }
+}
+
+impl JsonObject {
+    pub fn builder() -> JsonObjectBuilder {
+        JsonObjectBuilder::default()
+    }
+}
+
+impl JsonArray {
+    pub fn builder() -> JsonArrayBuilder {
+        JsonArrayBuilder::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_nested_values() {
+        let value: JsonValue = JsonObject::builder()
+            .insert("name", "widget")
+            .insert("count", 5)
+            .insert(
+                "tags",
+                JsonArray::builder()
+                    .push("a")
+                    .push(1.5)
+                    .push(None::<bool>)
+                    .push(JsonObject::builder().insert("nested", true).build())
+                    .build(),
+            )
+            .insert("empty", JsonObject::builder().build())
+            .insert("count", 6)
+            .build()
+            .into();
+        let parsed = crate::parse(
+            r#"{"name": "widget", "count": 6, "tags": ["a", 1.5, null, {"nested": true}], "empty": {}}"#,
+        )
+        .unwrap();
+        assert_eq!(value, parsed);
+        assert_eq!(
+            value.to_string(),
+            r#"{"name":"widget","count":6,"tags":["a",1.5,null,{"nested":true}],"empty":{}}"#
+        );
+        assert_eq!(JsonArray::builder().build(), JsonArray::default());
+    }
+}
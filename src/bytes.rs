@@ -0,0 +1,248 @@
+use crate::{CharIterator, ParseError};
+
+/// A [`CharIterator`] that walks UTF-8 bytes directly. ASCII, which is all
+/// JSON allows outside of strings, is handed out without any decoding, and
+/// runs inside strings are returned as slices of the input.
+pub(crate) struct Utf8ByteIter<'a> {
+    source: &'a str,
+    position: usize,
+}
+
+impl<'a> Utf8ByteIter<'a> {
+    /// Validates `bytes` as UTF-8 up front, so the rest of the parse can
+    /// slice it freely
+    pub(crate) fn new(bytes: &'a [u8]) -> Result<Self, ParseError> {
+        match core::str::from_utf8(bytes) {
+            Ok(source) => Ok(Self::from_str(source)),
+            Err(e) => {
+                let byte_index = e.valid_up_to();
+                // The prefix was just validated, so this can't fail
+                let valid = core::str::from_utf8(&bytes[..byte_index]).unwrap();
+                Err(ParseError::InvalidUtf8 {
+                    char_index: valid.chars().count(),
+                    byte_index,
+                })
+            }
+        }
+    }
+
+    pub(crate) fn from_str(source: &'a str) -> Self {
+        Self {
+            source,
+            position: 0,
+        }
+    }
+
+    /// Returns the next character and its length in bytes
+    #[inline]
+    fn decode_next(&self) -> Option<(char, usize)> {
+        let byte = *self.source.as_bytes().get(self.position)?;
+        if byte.is_ascii() {
+            return Some((byte as char, 1));
+        }
+        let c = Iterator::next(&mut self.source[self.position..].chars())?;
+        Some((c, c.len_utf8()))
+    }
+}
+
+impl CharIterator for Utf8ByteIter<'_> {
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        let (c, len) = self.decode_next()?;
+        self.position += len;
+        Some(c)
+    }
+
+    fn take_str_while(&mut self, func: impl Fn(char) -> bool) -> Option<&str> {
+        let start = self.position;
+        let bytes = self.source.as_bytes();
+        while let Some(&byte) = bytes.get(self.position) {
+            if byte.is_ascii() {
+                if !func(byte as char) {
+                    break;
+                }
+                self.position += 1;
+            } else {
+                let (c, len) = self.decode_next()?;
+                if !func(c) {
+                    break;
+                }
+                self.position += len;
+            }
+        }
+        Some(&self.source[start..self.position])
+    }
+
+    fn skip_prefix(&mut self, prefix: &str) -> bool {
+        let matches = self.source.as_bytes()[self.position..].starts_with(prefix.as_bytes());
+        if matches {
+            self.position += prefix.len();
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::agreement::{assert_agrees_with_parse, parsers};
+    use crate::{parse, parse_bytes, ParseError, Parser};
+
+    #[test]
+    fn byte_path_agrees_with_char_path() {
+        let mut parsers = parsers();
+        parsers.push(Parser::new().allow_non_string_keys(true));
+        assert_agrees_with_parse(&parsers, |p, s| p.parse_bytes(s.as_bytes()), |v| v);
+    }
+
+    #[test]
+    fn keyword_fast_path_agrees_with_char_by_char() {
+        let documents = [
+            "true",
+            "[true,false,null]",
+            "{\"é\":false, \"n\": null}",
+            "t",
+            "tru",
+            "[fals",
+            "nul",
+            "[truе]",
+            "trux",
+            "[nulll]",
+            "falsetrue",
+            "[true",
+            "[t",
+        ];
+        for document in documents {
+            // `parse_chars` has no backing `str`, so it always goes one
+            // character at a time
+            let slow = format!("{:?}", Parser::new().parse_chars(document.chars()));
+            let from_str = format!("{:?}", parse(document));
+            let from_bytes = format!("{:?}", parse_bytes(document.as_bytes()));
+            assert_eq!(from_str, slow, "{document:?}");
+            assert_eq!(from_bytes, slow, "{document:?}");
+        }
+        assert_eq!(crate::parse_prefix("null, 1").unwrap().1, 4);
+    }
+
+    #[test]
+    fn positions_survive_long_whitespace_runs() {
+        let indent = format!("\n{}", " ".repeat(10_000));
+        let document = format!("[1,{indent}\t{indent}é]");
+        let expected = crate::ParseError::ExpectedValue {
+            found: 'é',
+            char_index: 3 + 2 * indent.len() + 1,
+            byte_index: 3 + 2 * indent.len() + 1,
+        };
+        assert_eq!(parse(&document), Err(expected.clone()));
+        assert_eq!(parse_bytes(document.as_bytes()), Err(expected.clone()));
+        assert_eq!(Parser::new().parse_chars(document.chars()), Err(expected));
+
+        let document = document.replace('é', "2");
+        let (value, layout) = Parser::new().parse_with_layout(&document).unwrap();
+        assert_eq!(value, json!([1, 2]));
+        assert_eq!(layout.iter().collect::<Vec<_>>(), [(document.len() - 2, 2)]);
+
+        // Non-ASCII whitespace makes char and byte indices differ
+        let parser = Parser::new().whitespace(&[' ', '\u{A0}']);
+        let document = format!("[{}x]", "\u{A0} ".repeat(5_000));
+        let expected = crate::ParseError::ExpectedValue {
+            found: 'x',
+            char_index: 10_001,
+            byte_index: 15_001,
+        };
+        assert_eq!(parser.parse(&document), Err(expected.clone()));
+        assert_eq!(
+            parser.parse_bytes(document.as_bytes()),
+            Err(expected.clone())
+        );
+        assert_eq!(parser.parse_chars(document.chars()), Err(expected));
+    }
+
+    #[test]
+    fn invalid_utf8_is_reported() {
+        assert_eq!(
+            parse_bytes(b"[\"\xC3\xA9\xFF\"]"),
+            Err(ParseError::InvalidUtf8 {
+                char_index: 3,
+                byte_index: 4
+            })
+        );
+    }
+
+    /// Prints how long each of the named `runs` takes over `document`, on
+    /// average over 10 runs
+    fn bench(document: &str, runs: &[(&str, &dyn Fn())]) {
+        let timings: Vec<String> = runs
+            .iter()
+            .map(|(name, run)| {
+                let start = std::time::Instant::now();
+                for _ in 0..10 {
+                    run();
+                }
+                format!("{name} {:?}", start.elapsed() / 10)
+            })
+            .collect();
+        println!("{} bytes: {}", document.len(), timings.join(", "));
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_byte_path() {
+        let element = r#"{"id": 12345, "name": "some fairly ordinary ascii text", "tags": ["a", "b"], "ok": true}"#;
+        let document = format!("[{}]", vec![element; 20_000].join(","));
+        bench(
+            &document,
+            &[
+                ("chars", &|| assert!(parse(&document).is_ok())),
+                ("bytes", &|| {
+                    assert!(parse_bytes(document.as_bytes()).is_ok())
+                }),
+            ],
+        );
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_keywords() {
+        let document = format!("[{}]", vec!["true, false, null"; 100_000].join(","));
+        bench(
+            &document,
+            &[
+                ("char by char", &|| {
+                    assert!(Parser::new().parse_chars(document.chars()).is_ok())
+                }),
+                ("chars", &|| assert!(parse(&document).is_ok())),
+                ("bytes", &|| {
+                    assert!(parse_bytes(document.as_bytes()).is_ok())
+                }),
+            ],
+        );
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_indented() {
+        let mut document = String::new();
+        for depth in 0..2_000 {
+            document.push_str(&format!("{}[\n", " ".repeat(depth * 4)));
+        }
+        for depth in (0..2_000).rev() {
+            document.push_str(&format!("{}]\n", " ".repeat(depth * 4)));
+        }
+        let parser = Parser::new().max_depth(2_000);
+        bench(
+            &document,
+            &[
+                ("char by char", &|| {
+                    assert!(parser.parse_chars(document.chars()).is_ok())
+                }),
+                ("chars", &|| assert!(parser.parse(&document).is_ok())),
+                ("bytes", &|| {
+                    assert!(parser.parse_bytes(document.as_bytes()).is_ok())
+                }),
+            ],
+        );
+    }
+}
@@ -0,0 +1,563 @@
+use crate::{JsonArray, JsonBool, JsonNull, JsonNumber, JsonObject, JsonString, JsonValue};
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+impl From<&str> for JsonValue {
+    fn from(s: &str) -> Self {
+        JsonValue::String(JsonString(s.to_string()))
+    }
+}
+
+impl From<String> for JsonValue {
+    fn from(s: String) -> Self {
+        JsonValue::String(JsonString(s))
+    }
+}
+
+impl From<bool> for JsonValue {
+    fn from(b: bool) -> Self {
+        JsonValue::Bool(JsonBool(b))
+    }
+}
+
+impl From<Vec<JsonValue>> for JsonValue {
+    fn from(v: Vec<JsonValue>) -> Self {
+        JsonValue::Array(JsonArray(v))
+    }
+}
+
+impl From<JsonArray> for JsonValue {
+    fn from(array: JsonArray) -> Self {
+        JsonValue::Array(array)
+    }
+}
+
+impl From<JsonObject> for JsonValue {
+    fn from(object: JsonObject) -> Self {
+        JsonValue::Object(object)
+    }
+}
+
+/// Members are in the map's iteration order, which is arbitrary
+#[cfg(feature = "std")]
+impl From<HashMap<String, JsonValue>> for JsonObject {
+    fn from(map: HashMap<String, JsonValue>) -> Self {
+        JsonObject(map.into_iter().map(|(k, v)| (JsonString(k), v)).collect())
+    }
+}
+
+impl JsonObject {
+    /// The members keyed by plain `String`s. A `HashMap` has no order, so
+    /// the object's insertion order is lost.
+    #[cfg(feature = "std")]
+    pub fn into_map(self) -> HashMap<String, JsonValue> {
+        self.0.into_iter().map(|(k, v)| (k.0, v)).collect()
+    }
+}
+
+/// `None` becomes `null`
+impl<T: Into<JsonValue>> From<Option<T>> for JsonValue {
+    fn from(option: Option<T>) -> Self {
+        match option {
+            Some(value) => value.into(),
+            None => JsonValue::Null(JsonNull),
+        }
+    }
+}
+
+/// Numbers are stored as `f64`, so integers beyond 2^53 lose precision
+macro_rules! impl_from_number {
+    ($($t:ty)*) => {
+        $(
+            impl From<$t> for JsonValue {
+                fn from(n: $t) -> Self {
+                    JsonValue::Number(JsonNumber(n as f64, None))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_number!(i8 i16 i32 i64 isize u8 u16 u32 u64 usize f32 f64);
+
+/// Returned when converting a `JsonValue` into a Rust type it doesn't hold
+#[derive(Debug)]
+pub struct ConversionError {
+    /// Name of the JSON type the conversion required
+    pub expected: &'static str,
+    /// [`JsonValue::type_name`] of the value that was actually there
+    pub found: &'static str,
+}
+
+impl ConversionError {
+    /// Names `found` by its [`JsonValue::type_name`]
+    pub fn new(expected: &'static str, found: &JsonValue) -> Self {
+        Self {
+            expected,
+            found: found.type_name(),
+        }
+    }
+}
+
+impl core::fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "expected {}, found {}", self.expected, self.found)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConversionError {}
+
+impl TryFrom<JsonValue> for String {
+    type Error = ConversionError;
+
+    fn try_from(value: JsonValue) -> Result<Self, Self::Error> {
+        match value {
+            JsonValue::String(JsonString(s)) => Ok(s),
+            other => Err(ConversionError::new("string", &other)),
+        }
+    }
+}
+
+impl TryFrom<JsonValue> for f64 {
+    type Error = ConversionError;
+
+    fn try_from(value: JsonValue) -> Result<Self, Self::Error> {
+        match value {
+            JsonValue::Number(JsonNumber(n, _)) => Ok(n),
+            JsonValue::RawNumber(n) => Ok(n.as_f64()),
+            other => Err(ConversionError::new("number", &other)),
+        }
+    }
+}
+
+impl TryFrom<JsonValue> for bool {
+    type Error = ConversionError;
+
+    fn try_from(value: JsonValue) -> Result<Self, Self::Error> {
+        match value {
+            JsonValue::Bool(JsonBool(b)) => Ok(b),
+            other => Err(ConversionError::new("bool", &other)),
+        }
+    }
+}
+
+impl TryFrom<JsonValue> for Vec<JsonValue> {
+    type Error = ConversionError;
+
+    fn try_from(value: JsonValue) -> Result<Self, Self::Error> {
+        match value {
+            JsonValue::Array(JsonArray(v)) => Ok(v),
+            other => Err(ConversionError::new("array", &other)),
+        }
+    }
+}
+
+/// Conversion from a borrowed [`JsonValue`] into a Rust type, the
+/// counterpart to serde's `Deserialize` without the dependency. Implement it
+/// for your own structs by reading fields out of the object:
+///
+/// ```
+/// use json_parser::{parse, ConversionError, FromJson, JsonNull, JsonValue};
+///
+/// struct Point {
+///     x: f64,
+///     y: f64,
+/// }
+///
+/// impl FromJson for Point {
+///     fn from_json(value: &JsonValue) -> Result<Self, ConversionError> {
+///         let JsonValue::Object(object) = value else {
+///             return Err(ConversionError::new("object", value));
+///         };
+///         // A missing field reads as null
+///         let field = |key| object.get(key).unwrap_or(&JsonValue::Null(JsonNull));
+///         Ok(Point {
+///             x: f64::from_json(field("x"))?,
+///             y: f64::from_json(field("y"))?,
+///         })
+///     }
+/// }
+///
+/// let point = Point::from_json(&parse(r#"{"x": 1, "y": -2.5}"#).unwrap()).unwrap();
+/// assert_eq!((point.x, point.y), (1.0, -2.5));
+/// assert!(Point::from_json(&parse(r#"{"x": 1}"#).unwrap()).is_err());
+/// ```
+pub trait FromJson: Sized {
+    fn from_json(value: &JsonValue) -> Result<Self, ConversionError>;
+}
+
+impl FromJson for String {
+    fn from_json(value: &JsonValue) -> Result<Self, ConversionError> {
+        match value {
+            JsonValue::String(JsonString(s)) => Ok(s.clone()),
+            other => Err(ConversionError::new("string", other)),
+        }
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &JsonValue) -> Result<Self, ConversionError> {
+        match value {
+            JsonValue::Bool(JsonBool(b)) => Ok(*b),
+            other => Err(ConversionError::new("bool", other)),
+        }
+    }
+}
+
+impl FromJson for f64 {
+    fn from_json(value: &JsonValue) -> Result<Self, ConversionError> {
+        match value {
+            JsonValue::Number(JsonNumber(n, _)) => Ok(*n),
+            JsonValue::RawNumber(n) => Ok(n.as_f64()),
+            other => Err(ConversionError::new("number", other)),
+        }
+    }
+}
+
+impl FromJson for f32 {
+    fn from_json(value: &JsonValue) -> Result<Self, ConversionError> {
+        f64::from_json(value).map(|n| n as f32)
+    }
+}
+
+/// Only integral numbers in the target type's range convert, anything else is
+/// an error naming the target type
+macro_rules! impl_from_json_integer {
+    ($($t:ty)*) => {
+        $(
+            impl FromJson for $t {
+                fn from_json(value: &JsonValue) -> Result<Self, ConversionError> {
+                    let integer = match value {
+                        JsonValue::Number(n) => {
+                            n.as_i64().map(i128::from).or(n.as_u64().map(i128::from))
+                        }
+                        JsonValue::RawNumber(n) => {
+                            n.as_i64().map(i128::from).or(n.as_u64().map(i128::from))
+                        }
+                        _ => None,
+                    };
+                    integer
+                        .and_then(|integer| integer.try_into().ok())
+                        .ok_or(ConversionError::new(stringify!($t), value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_json_integer!(i8 i16 i32 i64 isize u8 u16 u32 u64 usize);
+
+/// `null` becomes `None`
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &JsonValue) -> Result<Self, ConversionError> {
+        match value {
+            JsonValue::Null(_) => Ok(None),
+            other => T::from_json(other).map(Some),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &JsonValue) -> Result<Self, ConversionError> {
+        match value {
+            JsonValue::Array(array) => array.iter().map(T::from_json).collect(),
+            other => Err(ConversionError::new("array", other)),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: FromJson> FromJson for HashMap<String, T> {
+    fn from_json(value: &JsonValue) -> Result<Self, ConversionError> {
+        match value {
+            JsonValue::Object(object) => object
+                .iter()
+                .map(|(key, value)| Ok((key.0.clone(), T::from_json(value)?)))
+                .collect(),
+            other => Err(ConversionError::new("object", other)),
+        }
+    }
+}
+
+/// Conversion from a Rust type into a [`JsonValue`], the counterpart to
+/// [`FromJson`]. Primitives go through the `From` conversions, implement it
+/// for your own structs by building an object.
+pub trait ToJson {
+    fn to_json(&self) -> JsonValue;
+}
+
+/// For types that have a `From` conversion and are cheap to copy
+macro_rules! impl_to_json_via_from {
+    ($($t:ty)*) => {
+        $(
+            impl ToJson for $t {
+                fn to_json(&self) -> JsonValue {
+                    JsonValue::from(*self)
+                }
+            }
+        )*
+    };
+}
+
+impl_to_json_via_from!(bool i8 i16 i32 i64 isize u8 u16 u32 u64 usize f32 f64);
+
+impl ToJson for str {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::from(self)
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::from(self.as_str())
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> JsonValue {
+        (**self).to_json()
+    }
+}
+
+/// `None` becomes `null`
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            Some(value) => value.to_json(),
+            None => JsonValue::Null(JsonNull),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Array(JsonArray(self.iter().map(T::to_json).collect()))
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> JsonValue {
+        self.as_slice().to_json()
+    }
+}
+
+/// Members are in the map's iteration order, which for a `HashMap` is
+/// arbitrary
+#[cfg(feature = "std")]
+impl<T: ToJson> ToJson for HashMap<String, T> {
+    fn to_json(&self) -> JsonValue {
+        members_to_json(self)
+    }
+}
+
+impl<T: ToJson> ToJson for BTreeMap<String, T> {
+    fn to_json(&self) -> JsonValue {
+        members_to_json(self)
+    }
+}
+
+fn members_to_json<'a, T: ToJson + 'a>(
+    members: impl IntoIterator<Item = (&'a String, &'a T)>,
+) -> JsonValue {
+    // Map keys are already unique
+    JsonValue::Object(JsonObject(
+        members
+            .into_iter()
+            .map(|(key, value)| (JsonString(key.clone()), value.to_json()))
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strings() {
+        let expected = JsonValue::String(JsonString("abc".to_string()));
+        assert!(JsonValue::from("abc") == expected);
+        assert!(JsonValue::from("abc".to_string()) == expected);
+    }
+
+    #[test]
+    fn numbers() {
+        assert!(JsonValue::from(1.5f64) == JsonValue::Number(JsonNumber(1.5, None)));
+        assert!(JsonValue::from(-7i64) == JsonValue::Number(JsonNumber(-7.0, None)));
+        assert!(JsonValue::from(7u64) == JsonValue::Number(JsonNumber(7.0, None)));
+    }
+
+    #[test]
+    fn bools() {
+        assert!(JsonValue::from(true) == JsonValue::Bool(JsonBool(true)));
+        assert!(JsonValue::from(false) == JsonValue::Bool(JsonBool(false)));
+    }
+
+    #[test]
+    fn vecs() {
+        let value = JsonValue::from(vec![JsonValue::from(1i64), JsonValue::from("a")]);
+        assert!(
+            value
+                == JsonValue::Array(JsonArray(vec![
+                    JsonValue::Number(JsonNumber(1.0, None)),
+                    JsonValue::String(JsonString("a".to_string())),
+                ]))
+        );
+    }
+
+    #[test]
+    fn options() {
+        assert!(JsonValue::from(None::<bool>) == JsonValue::Null(JsonNull));
+        assert!(JsonValue::from(Some("a")) == JsonValue::String(JsonString("a".to_string())));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn objects_round_trip_through_hash_maps() {
+        let JsonValue::Object(object) = json!({"a": 1, "b": [true], "c": {"d": null}}) else {
+            panic!("expected an object");
+        };
+        let map = object.clone().into_map();
+        assert_eq!(map.len(), 3);
+        assert_eq!(map["a"], json!(1));
+        assert_eq!(map["c"], json!({"d": null}));
+        let back = JsonObject::from(map);
+        assert_eq!(back, object);
+        assert_eq!(JsonObject::from(HashMap::new()), JsonObject::default());
+    }
+
+    #[test]
+    fn try_from_matching_variant() {
+        let s: Result<String, _> = JsonValue::from("a").try_into();
+        assert!(matches!(s, Ok(s) if s == "a"));
+        let n: Result<f64, _> = JsonValue::from(2.5).try_into();
+        assert!(matches!(n, Ok(n) if n == 2.5));
+        let b: Result<bool, _> = JsonValue::from(true).try_into();
+        assert!(matches!(b, Ok(true)));
+        let v: Result<Vec<JsonValue>, _> = JsonValue::from(vec![JsonValue::from(1)]).try_into();
+        assert!(matches!(v, Ok(v) if v.len() == 1));
+    }
+
+    #[test]
+    fn try_from_mismatched_variant() {
+        let s: Result<String, _> = JsonValue::from(1).try_into();
+        assert!(matches!(
+            s,
+            Err(ConversionError {
+                expected: "string",
+                found: "number"
+            })
+        ));
+        let n: Result<f64, _> = JsonValue::from("1").try_into();
+        assert!(matches!(
+            n,
+            Err(ConversionError {
+                expected: "number",
+                found: "string"
+            })
+        ));
+        let b: Result<bool, _> = JsonValue::Null(JsonNull).try_into();
+        assert!(matches!(
+            b,
+            Err(ConversionError {
+                expected: "bool",
+                found: "null"
+            })
+        ));
+        let v: Result<Vec<JsonValue>, _> = JsonValue::from(false).try_into();
+        let v = v.unwrap_err();
+        assert_eq!(v.to_string(), "expected array, found bool");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn from_json_nested_collections() {
+        let value = crate::parse(
+            r#"{"primes": [2, 3, 5], "names": {"a": ["x"], "b": []}, "maybe": [1.5, null]}"#,
+        )
+        .unwrap();
+        let JsonValue::Object(object) = &value else {
+            panic!("expected an object");
+        };
+        let primes = Vec::<u8>::from_json(object.get("primes").unwrap());
+        assert_eq!(primes.unwrap(), [2, 3, 5]);
+        let names = HashMap::<String, Vec<String>>::from_json(object.get("names").unwrap());
+        let expected = HashMap::from([
+            ("a".to_string(), vec!["x".to_string()]),
+            ("b".to_string(), vec![]),
+        ]);
+        assert_eq!(names.unwrap(), expected);
+        let maybe = Vec::<Option<f64>>::from_json(object.get("maybe").unwrap());
+        assert_eq!(maybe.unwrap(), [Some(1.5), None]);
+
+        let nested = HashMap::<String, HashMap<String, Vec<String>>>::from_json(&json!({
+            "outer": {"inner": ["v"]}
+        }));
+        assert_eq!(nested.unwrap()["outer"]["inner"], ["v"]);
+    }
+
+    #[test]
+    fn from_json_mismatches() {
+        let error = Vec::<u8>::from_json(&json!([1, 256])).unwrap_err();
+        assert_eq!(error.to_string(), "expected u8, found number");
+        let error = i64::from_json(&json!(1.5)).unwrap_err();
+        assert_eq!(error.to_string(), "expected i64, found number");
+        #[cfg(feature = "std")]
+        {
+            let error = HashMap::<String, bool>::from_json(&json!([true])).unwrap_err();
+            assert_eq!(error.to_string(), "expected object, found array");
+        }
+        let error = Option::<String>::from_json(&json!(false)).unwrap_err();
+        assert_eq!(error.to_string(), "expected string, found bool");
+        assert_eq!(u64::from_json(&json!(-0.0)).unwrap(), 0);
+    }
+
+    #[test]
+    fn to_json_round_trips_through_display() {
+        struct Score {
+            name: String,
+            points: i64,
+        }
+
+        impl ToJson for Score {
+            fn to_json(&self) -> JsonValue {
+                let mut object = JsonObject(Vec::new());
+                object.insert(JsonString("name".to_string()), self.name.to_json());
+                object.insert(JsonString("points".to_string()), self.points.to_json());
+                JsonValue::Object(object)
+            }
+        }
+
+        let scores = vec![
+            Score {
+                name: "ann".to_string(),
+                points: 12,
+            },
+            Score {
+                name: "bo \"b\"".to_string(),
+                points: -3,
+            },
+        ];
+        let text = scores.to_json().to_string();
+        assert_eq!(
+            text,
+            r#"[{"name":"ann","points":12},{"name":"bo \"b\"","points":-3}]"#
+        );
+        assert_eq!(crate::parse(&text).unwrap(), scores.to_json());
+
+        let pairs = vec![("a".to_string(), 1i64), ("b".to_string(), 2)];
+        let map: BTreeMap<String, Option<i64>> =
+            pairs.into_iter().map(|(k, v)| (k, Some(v))).collect();
+        assert_eq!(map.to_json().to_string(), r#"{"a":1,"b":2}"#);
+        #[cfg(feature = "std")]
+        {
+            let back = HashMap::<String, Option<i64>>::from_json(&map.to_json()).unwrap();
+            assert_eq!(back, map.into_iter().collect());
+        }
+        assert_eq!(None::<&str>.to_json().to_string(), "null");
+        assert_eq!([1.5f32, 2.0].to_json().to_string(), "[1.5,2]");
+    }
+}
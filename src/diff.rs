@@ -0,0 +1,214 @@
+use crate::members::{KeyIndex, Member};
+use crate::pointer::push_token;
+use crate::JsonValue;
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// One difference found by [`JsonValue::diff`]. Paths are JSON Pointers
+/// (RFC 6901), `""` being the whole document.
+#[derive(Debug, PartialEq)]
+pub enum Change<'a> {
+    /// Only present in the new document
+    Added { path: String, value: &'a JsonValue },
+    /// Only present in the old document
+    Removed { path: String, value: &'a JsonValue },
+    /// Present in both with different scalars, or with different kinds of
+    /// value. Containers that differ are descended into instead.
+    Modified {
+        path: String,
+        old: &'a JsonValue,
+        new: &'a JsonValue,
+    },
+}
+
+fn diff_at<'a>(
+    path: String,
+    old: &'a JsonValue,
+    new: &'a JsonValue,
+    changes: &mut Vec<Change<'a>>,
+) {
+    match (old, new) {
+        (JsonValue::Object(old), JsonValue::Object(new)) => {
+            diff_members(&path, &old.0, &new.0, |key| Cow::Borrowed(&key.0), changes);
+        }
+        (JsonValue::KeyedObject(old), JsonValue::KeyedObject(new)) => {
+            diff_members(&path, &old.0, &new.0, |key| key.text(), changes);
+        }
+        (JsonValue::Array(old), JsonValue::Array(new)) => {
+            let (old, new) = (&old.0, &new.0);
+            for index in 0..old.len().max(new.len()) {
+                let child = push_token(&path, &index.to_string());
+                match (old.get(index), new.get(index)) {
+                    (Some(old_value), Some(new_value)) => {
+                        diff_at(child, old_value, new_value, changes)
+                    }
+                    (Some(old_value), None) => changes.push(Change::Removed {
+                        path: child,
+                        value: old_value,
+                    }),
+                    (None, Some(new_value)) => changes.push(Change::Added {
+                        path: child,
+                        value: new_value,
+                    }),
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+        _ if old != new => changes.push(Change::Modified { path, old, new }),
+        _ => {}
+    }
+}
+
+/// [`diff_at`] for the members of two objects, `token` naming a key in paths
+fn diff_members<'a, K>(
+    path: &str,
+    old: &'a [(K, JsonValue)],
+    new: &'a [(K, JsonValue)],
+    token: fn(&K) -> Cow<'_, str>,
+    changes: &mut Vec<Change<'a>>,
+) where
+    (K, JsonValue): Member<Key = K>,
+{
+    let (mut old_index, mut new_index) = (KeyIndex::default(), KeyIndex::default());
+    for (key, old_value) in old {
+        let child = push_token(path, &token(key));
+        match new_index.get(new, key) {
+            Some((_, new_value)) => diff_at(child, old_value, new_value, changes),
+            None => changes.push(Change::Removed {
+                path: child,
+                value: old_value,
+            }),
+        }
+    }
+    for (key, new_value) in new {
+        if old_index.position(old, key).is_none() {
+            changes.push(Change::Added {
+                path: push_token(path, &token(key)),
+                value: new_value,
+            });
+        }
+    }
+}
+
+impl JsonValue {
+    /// Structural differences going from `self` to `other`, in depth-first
+    /// order of `self` with additions to an object listed after its other
+    /// changes.
+    ///
+    /// Arrays are compared position by position, so an element inserted at
+    /// the front shows up as every later element being modified plus one
+    /// addition at the end.
+    pub fn diff<'a>(&'a self, other: &'a JsonValue) -> Vec<Change<'a>> {
+        let mut changes = Vec::new();
+        diff_at(String::new(), self, other, &mut changes);
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_between_similar_objects() {
+        let old = json!({
+            "name": "app",
+            "version": 1,
+            "features": ["a", "b", "c"],
+            "db": {"host": "localhost", "port": 5432},
+            "a/b~c": true
+        });
+        let new = json!({
+            "name": "app",
+            "version": 2,
+            "features": ["a", "x"],
+            "db": {"host": "localhost", "user": "admin"},
+            "a/b~c": null,
+            "debug": false
+        });
+        assert_eq!(
+            old.diff(&new),
+            [
+                Change::Modified {
+                    path: "/version".to_string(),
+                    old: &json!(1),
+                    new: &json!(2),
+                },
+                Change::Modified {
+                    path: "/features/1".to_string(),
+                    old: &json!("b"),
+                    new: &json!("x"),
+                },
+                Change::Removed {
+                    path: "/features/2".to_string(),
+                    value: &json!("c"),
+                },
+                Change::Removed {
+                    path: "/db/port".to_string(),
+                    value: &json!(5432),
+                },
+                Change::Added {
+                    path: "/db/user".to_string(),
+                    value: &json!("admin"),
+                },
+                Change::Modified {
+                    path: "/a~1b~0c".to_string(),
+                    old: &json!(true),
+                    new: &json!(null),
+                },
+                Change::Added {
+                    path: "/debug".to_string(),
+                    value: &json!(false),
+                },
+            ]
+        );
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn different_kinds_are_modified_at_the_root() {
+        let old = json!([1]);
+        let new = json!({"0": 1});
+        assert_eq!(
+            old.diff(&new),
+            [Change::Modified {
+                path: String::new(),
+                old: &old,
+                new: &new,
+            }]
+        );
+        assert_eq!(
+            json!([]).diff(&json!([[]])),
+            [Change::Added {
+                path: "/0".to_string(),
+                value: &json!([]),
+            }]
+        );
+    }
+
+    #[test]
+    fn keyed_objects_are_descended_into() {
+        let parser = crate::Parser::new().allow_non_string_keys(true);
+        let old = parser.parse(r#"{1: {"a": 1}, "b": 2}"#).unwrap();
+        let new = parser.parse(r#"{1: {"a": 3}, true: 2}"#).unwrap();
+        assert_eq!(
+            old.diff(&new),
+            [
+                Change::Modified {
+                    path: "/1/a".to_string(),
+                    old: &json!(1),
+                    new: &json!(3),
+                },
+                Change::Removed {
+                    path: "/b".to_string(),
+                    value: &json!(2),
+                },
+                Change::Added {
+                    path: "/true".to_string(),
+                    value: &json!(2),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use crate::members::{insert_member, KeyIndex, Member};
+use crate::{
+    expect_key_start, next_key_is_unquoted, parse_array_with, parse_identifier, parse_object_with,
+    unexpected_value_start, CharIterator, DuplicateKeyPolicy, JsonArray, JsonBool, JsonNull,
+    JsonNumber, JsonObject, JsonRawNumber, JsonString, JsonType, JsonValue, ParseError, Parser,
+    WhitespaceSkippingIndexTrackingIter,
+};
+use alloc::rc::Rc;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+/// A [`JsonValue`] whose object keys are interned, so every occurrence of
+/// the same key in a document shares one allocation. Meant for documents
+/// made of many objects with the same keys, like an array of records.
+///
+/// Objects keep the same semantics as [`JsonObject`]: insertion order, and a
+/// repeated key replaces the earlier value in place. String values aren't
+/// interned.
+#[derive(Debug, PartialEq)]
+pub enum JsonValueInterned {
+    Object(Vec<(Rc<str>, JsonValueInterned)>),
+    Array(Vec<JsonValueInterned>),
+    String(JsonString),
+    Number(JsonNumber),
+    /// Only produced with [`ParseOptions::raw_numbers`](crate::ParseOptions::raw_numbers)
+    RawNumber(JsonRawNumber),
+    Bool(JsonBool),
+    Null(JsonNull),
+}
+
+impl JsonValueInterned {
+    /// Gives every key its own `String` again
+    pub fn into_owned(self) -> JsonValue {
+        match self {
+            // Parsing already made the keys unique
+            JsonValueInterned::Object(members) => JsonValue::Object(JsonObject(
+                members
+                    .into_iter()
+                    .map(|(key, value)| (JsonString(key.to_string()), value.into_owned()))
+                    .collect(),
+            )),
+            JsonValueInterned::Array(elements) => JsonValue::Array(JsonArray(
+                elements.into_iter().map(Self::into_owned).collect(),
+            )),
+            JsonValueInterned::String(s) => JsonValue::String(s),
+            JsonValueInterned::Number(n) => JsonValue::Number(n),
+            JsonValueInterned::RawNumber(n) => JsonValue::RawNumber(n),
+            JsonValueInterned::Bool(b) => JsonValue::Bool(b),
+            JsonValueInterned::Null(n) => JsonValue::Null(n),
+        }
+    }
+}
+
+/// Every distinct key seen so far in the document
+#[cfg(feature = "std")]
+type KeyPool = std::collections::HashSet<Rc<str>>;
+#[cfg(not(feature = "std"))]
+type KeyPool = alloc::collections::BTreeSet<Rc<str>>;
+
+impl Parser {
+    /// Same as [`Parser::parse`], but object keys are interned. A key that
+    /// was already seen is looked up without allocating, so only the first
+    /// occurrence of each key costs an allocation. Keys are always strings,
+    /// [`ParseOptions::allow_non_string_keys`](crate::ParseOptions::allow_non_string_keys)
+    /// isn't supported.
+    pub fn parse_interned(&self, input: &str) -> Result<JsonValueInterned, ParseError> {
+        let mut pool = KeyPool::new();
+        self.parse_document_with(input.chars(), |i| parse_value(i, &mut pool))
+    }
+}
+
+/// [`Parser::parse_interned`] with the default [`Parser`]
+pub fn parse_interned(input: &str) -> Result<JsonValueInterned, ParseError> {
+    Parser::new().parse_interned(input)
+}
+
+fn parse_value<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    pool: &mut KeyPool,
+) -> Result<JsonValueInterned, ParseError> {
+    let next_char = i
+        .peek_non_whitespace()?
+        .ok_or(ParseError::UnexpectedEndOfString)?;
+    i.count_element()?;
+    match next_char {
+        '{' => parse_object(i, pool).map(JsonValueInterned::Object),
+        '[' => parse_array_with(i, |i| parse_value(i, pool)).map(JsonValueInterned::Array),
+        '"' => Ok(JsonValueInterned::String(JsonString::parse(i)?)),
+        '\'' if i.options.allow_single_quotes => {
+            Ok(JsonValueInterned::String(JsonString::parse(i)?))
+        }
+        c if i.options.starts_number(c) && i.options.raw_numbers => {
+            Ok(JsonValueInterned::RawNumber(JsonRawNumber::parse(i)?))
+        }
+        c if i.options.starts_number(c) => Ok(JsonValueInterned::Number(JsonNumber::parse(i)?)),
+        't' | 'f' => Ok(JsonValueInterned::Bool(JsonBool::parse(i)?)),
+        'n' => Ok(JsonValueInterned::Null(JsonNull::parse(i)?)),
+        _ => Err(unexpected_value_start(i)),
+    }
+}
+
+fn parse_object<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    pool: &mut KeyPool,
+) -> Result<Vec<(Rc<str>, JsonValueInterned)>, ParseError> {
+    let mut members: Vec<(Rc<str>, JsonValueInterned)> = Vec::new();
+    let mut index = KeyIndex::default();
+    parse_object_with(i, |i| {
+        let key_char_index = i.next_char_index();
+        let key_byte_index = i.next_byte_index;
+        let key = parse_key(i, pool)?;
+        let policy = i.options.duplicate_key_policy;
+        if policy == DuplicateKeyPolicy::Error && index.position(&members, &key).is_some() {
+            return Err(ParseError::DuplicateKey {
+                key: key.to_string(),
+                char_index: key_char_index,
+                byte_index: key_byte_index,
+            });
+        }
+        i.expect_specific_char_ignore_whitespace(':')?;
+        let value = parse_value(i, pool)?;
+        insert_member(&mut members, &mut index, (key, value), policy);
+        Ok(())
+    })?;
+    Ok(members)
+}
+
+impl Member for (Rc<str>, JsonValueInterned) {
+    type Key = str;
+
+    fn key(&self) -> &str {
+        &self.0
+    }
+
+    fn object_members(&mut self) -> Option<&mut Vec<Self>> {
+        match &mut self.1 {
+            JsonValueInterned::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+}
+
+/// Parses the key into the scratch buffer and only allocates if the pool
+/// doesn't have it yet
+fn parse_key<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    pool: &mut KeyPool,
+) -> Result<Rc<str>, ParseError> {
+    expect_key_start(i)?;
+    let mut key = i.take_scratch();
+    if next_key_is_unquoted(i)? {
+        parse_identifier(i, |c| key.push(c))?;
+    } else {
+        let quote = i.expect_opening_quote()?;
+        JsonString::parse_rest(i, &mut key, quote)?;
+    }
+    let interned = match pool.get(key.as_str()) {
+        Some(interned) => Rc::clone(interned),
+        None => {
+            let interned: Rc<str> = Rc::from(key.as_str());
+            pool.insert(Rc::clone(&interned));
+            interned
+        }
+    };
+    i.return_scratch(key);
+    Ok(interned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agreement::{assert_agrees_with_parse, parsers};
+
+    #[test]
+    fn repeated_keys_share_storage() {
+        let row = r#"{"id": 1, "name": "x", "nested": {"id": 2}}"#;
+        let input = format!("[{}]", vec![row; 1000].join(","));
+        let Ok(JsonValueInterned::Array(rows)) = parse_interned(&input) else {
+            panic!("expected an array");
+        };
+        assert_eq!(rows.len(), 1000);
+        let keys: Vec<&Rc<str>> = rows
+            .iter()
+            .flat_map(|row| {
+                let JsonValueInterned::Object(members) = row else {
+                    panic!("expected an object");
+                };
+                members.iter().map(|(key, _)| key)
+            })
+            .collect();
+        let first_id = keys[0];
+        assert_eq!(&**first_id, "id");
+        for key in keys.iter().filter(|key| key.as_ref() == "id") {
+            assert!(Rc::ptr_eq(key, first_id));
+        }
+        let JsonValueInterned::Object(members) = &rows[999] else {
+            panic!("expected an object");
+        };
+        let JsonValueInterned::Object(nested) = &members[2].1 else {
+            panic!("expected an object");
+        };
+        assert!(Rc::ptr_eq(&nested[0].0, first_id));
+        // One reference per occurrence of `id`, the pool is gone by now
+        assert_eq!(Rc::strong_count(first_id), 2000);
+    }
+
+    #[test]
+    fn agrees_with_owned_parse() {
+        assert_agrees_with_parse(
+            &parsers(),
+            |parser, input| {
+                parser
+                    .parse_interned(input)
+                    .map(JsonValueInterned::into_owned)
+            },
+            |value| value,
+        );
+    }
+}
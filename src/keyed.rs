@@ -0,0 +1,271 @@
+use crate::members::{insert_member, KeyIndex, Member};
+use crate::{
+    hash_members, next_key_is_unquoted, parse_object_with, CharIterator, DuplicateKeyPolicy,
+    JsonString, JsonType, JsonValue, ParseError, WhitespaceSkippingIndexTrackingIter,
+};
+use alloc::borrow::Cow;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+/// An object key that can be any scalar, see [`JsonKeyedObject`]. String keys
+/// are stored as [`JsonValue::String`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct JsonKey(pub(crate) JsonValue);
+
+impl JsonKey {
+    /// `None` for arrays and objects, which can't be keys
+    pub fn new(key: JsonValue) -> Option<Self> {
+        match key {
+            JsonValue::Array(_) | JsonValue::Object(_) | JsonValue::KeyedObject(_) => None,
+            key => Some(JsonKey(key)),
+        }
+    }
+
+    pub fn value(&self) -> &JsonValue {
+        &self.0
+    }
+
+    /// A string key's contents, unquoted like a [`JsonObject`](crate::JsonObject)
+    /// key, or the JSON text of any other key, e.g. `1`. Used where a key has
+    /// to be named, like in errors and JSON Pointers.
+    pub fn text(&self) -> Cow<'_, str> {
+        match &self.0 {
+            JsonValue::String(s) => Cow::Borrowed(&s.0),
+            key => Cow::Owned(key.to_string()),
+        }
+    }
+}
+
+/// An object whose keys can be any scalar, e.g. `{1: "one", true: "yes"}`.
+/// Only produced with
+/// [`ParseOptions::allow_non_string_keys`](crate::ParseOptions::allow_non_string_keys),
+/// in which case every object is parsed as one, even if all its keys are
+/// strings.
+///
+/// Otherwise it behaves like [`JsonObject`](crate::JsonObject): members are
+/// kept in insertion order and a repeated key replaces its value in place.
+/// Keys are compared as values, so `1` and `1.0` are the same key.
+///
+/// When serialized, string keys are quoted as usual and other keys are
+/// written as their JSON text, e.g. `{1:"one"}`. That isn't valid JSON, it
+/// only parses back with the same option.
+#[derive(Clone, Debug, Default)]
+pub struct JsonKeyedObject(pub(crate) Vec<(JsonKey, JsonValue)>);
+
+impl JsonKeyedObject {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, key: &JsonValue) -> Option<&JsonValue> {
+        self.0.iter().find(|(k, _)| k.0 == *key).map(|(_, v)| v)
+    }
+
+    /// Replaces the value in place if the key is already present
+    pub fn insert(&mut self, key: JsonKey, value: JsonValue) -> Option<JsonValue> {
+        match self.0.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => Some(core::mem::replace(existing, value)),
+            None => {
+                self.0.push((key, value));
+                None
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&JsonKey, &JsonValue)> {
+        self.0.iter().map(|(k, v)| (k, v))
+    }
+}
+
+/// Only keyed objects merge, as every object in the document is one
+impl Member for (JsonKey, JsonValue) {
+    type Key = JsonKey;
+
+    fn key(&self) -> &JsonKey {
+        &self.0
+    }
+
+    fn object_members(&mut self) -> Option<&mut Vec<Self>> {
+        match &mut self.1 {
+            JsonValue::KeyedObject(object) => Some(&mut object.0),
+            _ => None,
+        }
+    }
+}
+
+/// Member order doesn't affect equality, like [`JsonObject`](crate::JsonObject)
+impl PartialEq for JsonKeyedObject {
+    fn eq(&self, other: &Self) -> bool {
+        let mut index = KeyIndex::default();
+        self.len() == other.len()
+            && self.iter().all(|(k, v)| {
+                index
+                    .get(&other.0, k)
+                    .is_some_and(|(_, other_v)| v == other_v)
+            })
+    }
+}
+
+impl Eq for JsonKeyedObject {}
+
+/// Order independent, like [`JsonObject`](crate::JsonObject)
+impl core::hash::Hash for JsonKeyedObject {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        hash_members(&self.0, state);
+    }
+}
+
+impl<CI: CharIterator> JsonType<CI> for JsonKeyedObject {
+    fn parse(i: &mut WhitespaceSkippingIndexTrackingIter<CI>) -> Result<Self, ParseError> {
+        let mut object = JsonKeyedObject(Vec::new());
+        let mut index = KeyIndex::default();
+        parse_object_with(i, |i| {
+            let key_char_index = i.next_char_index();
+            let key_byte_index = i.next_byte_index;
+            let key = JsonKey::parse(i)?;
+            let policy = i.options.duplicate_key_policy;
+            if policy == DuplicateKeyPolicy::Error && index.position(&object.0, &key).is_some() {
+                return Err(ParseError::DuplicateKey {
+                    key: key.text().into_owned(),
+                    char_index: key_char_index,
+                    byte_index: key_byte_index,
+                });
+            }
+            i.expect_specific_char_ignore_whitespace(':')?;
+            let value = JsonValue::parse(i)?;
+            insert_member(&mut object.0, &mut index, (key, value), policy);
+            Ok(())
+        })?;
+        Ok(object)
+    }
+}
+
+/// Quoted and unquoted keys are parsed as usual, so with
+/// [`ParseOptions::allow_unquoted_keys`](crate::ParseOptions::allow_unquoted_keys)
+/// on, `true` and `null` are identifiers rather than literals. Anything else
+/// is parsed as a value, which mustn't be an array or object.
+impl<CI: CharIterator> JsonType<CI> for JsonKey {
+    fn parse(i: &mut WhitespaceSkippingIndexTrackingIter<CI>) -> Result<Self, ParseError> {
+        let next_char = i
+            .peek_non_whitespace()?
+            .ok_or(ParseError::UnexpectedEndOfString)?;
+        if matches!(next_char, '"' | '\'') || next_key_is_unquoted(i)? {
+            return Ok(JsonKey(JsonValue::String(JsonString::parse_key(i)?)));
+        }
+        if matches!(next_char, '{' | '[') {
+            i.next_any();
+            return Err(ParseError::ExpectedKey {
+                found: next_char,
+                char_index: i.previous_char_index(),
+                byte_index: i.previous_byte_index(),
+            });
+        }
+        JsonValue::parse_uncounted(i).map(JsonKey)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    #[test]
+    fn scalar_keys() {
+        let parser = Parser::new().allow_non_string_keys(true);
+        let value = parser
+            .parse(r#"{1: "one", "two": 2, true: [], null: {2.5: 0}}"#)
+            .unwrap();
+        let JsonValue::KeyedObject(object) = &value else {
+            panic!("expected a keyed object");
+        };
+        assert_eq!(object.len(), 4);
+        assert_eq!(object.get(&json!(1)), Some(&json!("one")));
+        assert_eq!(object.get(&json!("two")), Some(&json!(2)));
+        assert_eq!(object.get(&json!(true)), Some(&json!([])));
+        assert_eq!(
+            value.to_string(),
+            r#"{1:"one","two":2,true:[],null:{2.5:0}}"#
+        );
+        assert_eq!(parser.parse(&value.to_string()).unwrap(), value);
+
+        // Keys compare as values
+        let repeated = parser.parse(r#"{1: "a", 1.0: "b"}"#).unwrap();
+        assert_eq!(repeated.len(), Some(1));
+        let strict = parser.allow_duplicate_keys(false);
+        assert_eq!(
+            strict.parse(r#"{1: "a", 1e0: "b"}"#),
+            Err(ParseError::DuplicateKey {
+                key: "1".to_string(),
+                char_index: 9,
+                byte_index: 9
+            })
+        );
+        // Named unquoted, like in a `JsonObject`
+        assert_eq!(
+            strict.parse(r#"{"a": 1, "a": 2}"#),
+            Err(ParseError::DuplicateKey {
+                key: "a".to_string(),
+                char_index: 9,
+                byte_index: 9
+            })
+        );
+    }
+
+    #[test]
+    fn keys_count_only_as_part_of_their_member() {
+        // The object, each member and each value
+        let parser = Parser::new().allow_non_string_keys(true).max_elements(5);
+        for input in [r#"{1: 0, true: 0}"#, r#"{"1": 0, "true": 0}"#] {
+            assert!(parser.parse(input).is_ok(), "{input}");
+            assert!(parser.validate(input).is_ok(), "{input}");
+        }
+        let parser = parser.max_elements(4);
+        assert_eq!(
+            parser.parse("{1: 0, true: 0}"),
+            Err(ParseError::TooManyElements {
+                char_index: 13,
+                byte_index: 13,
+            })
+        );
+        assert_eq!(
+            parser.parse(r#"{"1": 0, "true": 0}"#),
+            Err(ParseError::TooManyElements {
+                char_index: 17,
+                byte_index: 17,
+            })
+        );
+    }
+
+    #[test]
+    fn rejected_keys() {
+        assert_eq!(
+            crate::parse(r#"{1: "one"}"#),
+            Err(ParseError::ExpectedKey {
+                found: '1',
+                char_index: 1,
+                byte_index: 1
+            })
+        );
+        let parser = Parser::new().allow_non_string_keys(true);
+        assert_eq!(
+            parser.parse(r#"{[1]: "one"}"#),
+            Err(ParseError::ExpectedKey {
+                found: '[',
+                char_index: 1,
+                byte_index: 1
+            })
+        );
+        assert_eq!(
+            parser.parse("{x: 1}"),
+            Err(ParseError::ExpectedValue {
+                found: 'x',
+                char_index: 1,
+                byte_index: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{
+    parse_document_in, JsonType, JsonValue, ParseError, Parser, WhitespaceSkippingIndexTrackingIter,
+};
+use alloc::collections::BTreeMap;
+
+/// Where newlines appeared between the tokens of a document, for formatters
+/// that want to approximately keep the original grouping. Tokens are keys,
+/// values, `,`, `:` and brackets, identified by the char index they start at,
+/// which is the start of their span. Tokens on the same line as the previous
+/// one aren't recorded.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Layout {
+    newlines: BTreeMap<usize, usize>,
+}
+
+impl Layout {
+    /// Number of newlines between the previous token and the one starting at
+    /// `char_index`, counting those inside comments
+    pub fn newlines_before(&self, char_index: usize) -> usize {
+        self.newlines.get(&char_index).copied().unwrap_or(0)
+    }
+
+    /// Number of empty lines directly before the token starting at
+    /// `char_index`
+    pub fn blank_lines_before(&self, char_index: usize) -> usize {
+        self.newlines_before(char_index).saturating_sub(1)
+    }
+
+    /// Start index and newline count of every token preceded by a newline,
+    /// in document order
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.newlines.iter().map(|(&index, &count)| (index, count))
+    }
+}
+
+impl Parser {
+    /// Same as [`Parser::parse`], additionally recording the [`Layout`]
+    pub fn parse_with_layout(&self, input: &str) -> Result<(JsonValue, Layout), ParseError> {
+        let mut i =
+            WhitespaceSkippingIndexTrackingIter::with_options(input.chars(), self.options.clone());
+        i.newlines = Some(BTreeMap::new());
+        let value = parse_document_in(&mut i, JsonValue::parse)?;
+        let newlines = i.newlines.unwrap_or_default();
+        Ok((value, Layout { newlines }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_newlines_between_members() {
+        let input = "{\n  \"a\": 1,\n\n  \"b\": 2,\n\n\n  \"c\": [3,\n    4]\n}";
+        let (value, layout) = Parser::new().parse_with_layout(input).unwrap();
+        assert_eq!(value, json!({"a": 1, "b": 2, "c": [3, 4]}));
+
+        let index_of = |token: &str| input[..input.find(token).unwrap()].chars().count();
+        assert_eq!(layout.newlines_before(index_of("\"a\"")), 1);
+        assert_eq!(layout.blank_lines_before(index_of("\"a\"")), 0);
+        assert_eq!(layout.newlines_before(index_of("\"b\"")), 2);
+        assert_eq!(layout.blank_lines_before(index_of("\"b\"")), 1);
+        assert_eq!(layout.newlines_before(index_of("\"c\"")), 3);
+        assert_eq!(layout.blank_lines_before(index_of("\"c\"")), 2);
+        assert_eq!(layout.newlines_before(index_of("4")), 1);
+        assert_eq!(layout.newlines_before(index_of("1")), 0);
+        assert_eq!(layout.newlines_before(input.chars().count() - 1), 1);
+        assert_eq!(layout.iter().count(), 5);
+    }
+
+    #[test]
+    fn counts_newlines_in_comments() {
+        let parser = Parser::new().allow_comments(true);
+        let input = "[1, // one\n/* two\n\n */ 2]\n";
+        let (_, layout) = parser.parse_with_layout(input).unwrap();
+        assert_eq!(layout.iter().collect::<Vec<_>>(), [(23, 3)]);
+        let (_, layout) = parser.parse_with_layout("[1, 2]").unwrap();
+        assert_eq!(layout, Layout::default());
+    }
+}
@@ -0,0 +1,4178 @@
+// https://www.json.org/json-en.html
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+#[macro_use]
+mod macros;
+#[cfg(test)]
+mod agreement;
+mod borrowed;
+mod builder;
+mod bytes;
+mod convert;
+mod diff;
+mod interned;
+mod keyed;
+mod layout;
+mod members;
+mod merge;
+mod pointer;
+mod prune;
+#[cfg(feature = "std")]
+mod reader;
+mod recover;
+mod serialize;
+mod sorted;
+mod spanned;
+mod stats;
+mod stream;
+mod validate;
+mod visit;
+
+pub use borrowed::{parse_borrowed, JsonValueBorrowed};
+pub use builder::{JsonArrayBuilder, JsonObjectBuilder};
+pub use convert::{ConversionError, FromJson, ToJson};
+pub use diff::Change;
+pub use interned::{parse_interned, JsonValueInterned};
+pub use keyed::{JsonKey, JsonKeyedObject};
+pub use layout::Layout;
+pub use merge::deep_merge;
+pub use pointer::PointerError;
+pub use prune::PruneOptions;
+#[cfg(feature = "std")]
+pub use reader::{parse_file, parse_stream};
+pub use recover::Recovered;
+pub use serialize::SerializeOptions;
+#[cfg(feature = "std")]
+pub use serialize::{to_writer, to_writer_pretty, to_writer_with_options};
+pub use sorted::JsonObjectSorted;
+pub use spanned::{parse_spanned, JsonValueSpanned, Spanned, SpannedMember};
+pub use stats::DocumentStats;
+pub use stream::parse_array_stream;
+pub use validate::validate;
+pub use visit::Visitor;
+
+/// What [`json!`] expands to, for crates that don't link `alloc` themselves
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::string::String;
+    pub use alloc::vec;
+}
+
+/// The whitespace the spec allows between tokens
+pub const JSON_WHITESPACE: [char; 4] = [' ', '\n', '\r', '\t'];
+
+fn is_json_whitespace(c: char) -> bool {
+    JSON_WHITESPACE.contains(&c)
+}
+
+/// The characters skipped between tokens, see [`ParseOptions::whitespace`].
+/// It's checked for every character between tokens, so ASCII, which is all
+/// whitespace usually is, is looked up in a bitmask.
+#[derive(Clone, Debug)]
+pub struct WhitespaceSet {
+    /// Bit `n` is set if the ASCII character `n` is whitespace
+    ascii: u128,
+    other: Vec<char>,
+}
+
+impl WhitespaceSet {
+    pub fn new(whitespace: &[char]) -> Self {
+        let mut set = Self {
+            ascii: 0,
+            other: Vec::new(),
+        };
+        for &c in whitespace {
+            if c.is_ascii() {
+                set.ascii |= 1 << c as u32;
+            } else {
+                set.other.push(c);
+            }
+        }
+        set
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        if c.is_ascii() {
+            self.ascii & (1 << c as u32) != 0
+        } else {
+            self.other.contains(&c)
+        }
+    }
+}
+
+/// The source of characters for parsing. Lookahead is handled by
+/// [`WhitespaceSkippingIndexTrackingIter`], so implementors only move forward.
+trait CharIterator {
+    fn next(&mut self) -> Option<char>;
+
+    /// Consumes the longest run of characters matching `func` and returns it
+    /// as a single slice, so callers can copy it in bulk. Iterators that
+    /// aren't backed by a `str` return `None` without consuming anything.
+    fn take_str_while(&mut self, _func: impl Fn(char) -> bool) -> Option<&str> {
+        None
+    }
+
+    /// Consumes `prefix` with a single comparison if the input continues
+    /// with all of it, returning whether it did. Iterators that aren't backed
+    /// by a `str` return `false` without consuming anything.
+    fn skip_prefix(&mut self, _prefix: &str) -> bool {
+        false
+    }
+}
+
+impl CharIterator for core::str::Chars<'_> {
+    fn next(&mut self) -> Option<char> {
+        core::iter::Iterator::next(self)
+    }
+
+    fn take_str_while(&mut self, func: impl Fn(char) -> bool) -> Option<&str> {
+        let rest = self.as_str();
+        let end = rest.find(|c| !func(c)).unwrap_or(rest.len());
+        let (run, after) = rest.split_at(end);
+        *self = after.chars();
+        Some(run)
+    }
+
+    fn skip_prefix(&mut self, prefix: &str) -> bool {
+        match self.as_str().strip_prefix(prefix) {
+            Some(after) => {
+                *self = after.chars();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Drives the parser from any `Iterator<Item = char>`, see
+/// [`Parser::parse_chars`]. There's no backing `str`, so characters are
+/// always consumed one at a time.
+struct IterChars<I: Iterator<Item = char>>(I);
+
+impl<I: Iterator<Item = char>> CharIterator for IterChars<I> {
+    fn next(&mut self) -> Option<char> {
+        self.0.next()
+    }
+}
+
+struct WhitespaceSkippingIndexTrackingIter<CI: CharIterator> {
+    /// Index of the last consumed character, counted in `char`s
+    previously_outputted_index: Option<usize>,
+    /// Index of the last consumed character, counted in UTF-8 bytes
+    previously_outputted_byte_index: Option<usize>,
+    /// Byte offset of the next character to be consumed
+    next_byte_index: usize,
+    inner: CI,
+    /// Single character lookahead, already taken from `inner` but not yet
+    /// consumed, so it doesn't count towards the indices
+    peeked: Option<char>,
+    options: ParseOptions,
+    /// Number of arrays and objects currently being parsed
+    depth: usize,
+    /// Values and object members parsed so far, see
+    /// [`ParseOptions::max_elements`]
+    elements: usize,
+    /// Reused by string and number parsing so the buffer's capacity carries
+    /// over between values, see [`Self::take_scratch`]
+    scratch: String,
+    /// Number of newlines before each token, keyed by the token's starting
+    /// char index. `None` unless recording for [`Parser::parse_with_layout`].
+    newlines: Option<alloc::collections::BTreeMap<usize, usize>>,
+}
+
+impl<CI: CharIterator> WhitespaceSkippingIndexTrackingIter<CI> {
+    #[cfg(test)]
+    fn new(ci: CI) -> Self {
+        Self::with_options(ci, ParseOptions::default())
+    }
+
+    fn with_options(ci: CI, options: ParseOptions) -> Self {
+        Self {
+            previously_outputted_index: None,
+            previously_outputted_byte_index: None,
+            next_byte_index: 0,
+            inner: ci,
+            peeked: None,
+            options,
+            depth: 0,
+            elements: 0,
+            scratch: String::new(),
+            newlines: None,
+        }
+    }
+
+    /// Borrows the scratch buffer, cleared. Hand it back with
+    /// [`Self::return_scratch`] once done, otherwise the next value starts
+    /// from an empty allocation again.
+    fn take_scratch(&mut self) -> String {
+        let mut scratch = core::mem::take(&mut self.scratch);
+        scratch.clear();
+        scratch
+    }
+
+    fn return_scratch(&mut self, scratch: String) {
+        self.scratch = scratch;
+    }
+
+    /// Index of the last consumed character, counted in `char`s. Errors are
+    /// reported there, so before anything is consumed this is the start of
+    /// the input rather than a panic.
+    fn previous_char_index(&self) -> usize {
+        self.previously_outputted_index.unwrap_or(0)
+    }
+
+    /// Index of the last consumed character, counted in UTF-8 bytes, see
+    /// [`Self::previous_char_index`]
+    fn previous_byte_index(&self) -> usize {
+        self.previously_outputted_byte_index.unwrap_or(0)
+    }
+
+    /// How far parsing has got, for building errors or further parsing on
+    /// top. Peeked lookahead doesn't count as consumed. After a value is
+    /// parsed this is right after it, after an error it's usually right after
+    /// the offending character.
+    fn position(&self) -> Position {
+        Position {
+            char_index: self.next_char_index(),
+            byte_index: self.next_byte_index,
+        }
+    }
+
+    /// Index, counted in `char`s, of the next character to be consumed
+    fn next_char_index(&self) -> usize {
+        self.previously_outputted_index.map_or(0, |i| i + 1)
+    }
+
+    fn inc_index(&mut self, c: char) {
+        match &mut (self.previously_outputted_index) {
+            None => self.previously_outputted_index = Some(0),
+            Some(i) => *i += 1,
+        }
+        self.previously_outputted_byte_index = Some(self.next_byte_index);
+        self.next_byte_index += c.len_utf8();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        if self.peeked.is_none() {
+            self.peeked = self.inner.next();
+        }
+        self.peeked
+    }
+
+    fn next_any(&mut self) -> Option<char> {
+        let out = self.peeked.take().or_else(|| self.inner.next());
+        if let Some(c) = out {
+            self.inc_index(c);
+        }
+        out
+    }
+
+    /// Consumes up to and including `end`, returns whether it was found
+    /// before the input ran out
+    fn next_any_until(&mut self, end: char) -> bool {
+        while let Some(c) = self.next_any() {
+            if c == end {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn next_if(&mut self, func: impl FnOnce(&char) -> bool) -> Option<char> {
+        let c = self.peek()?;
+        if func(&c) {
+            self.next_any()
+        } else {
+            None
+        }
+    }
+
+    /// Consumes a run of characters matching `func` in one go if the backing
+    /// iterator supports it, see [`CharIterator::take_str_while`]. A run
+    /// can't start with a buffered lookahead character, in that case `None`
+    /// is returned so the caller consumes it on its own.
+    fn take_str_while(&mut self, func: impl Fn(char) -> bool) -> Option<&str> {
+        if let Some(peeked) = self.peeked {
+            return if func(peeked) { None } else { Some("") };
+        }
+        let run = self.inner.take_str_while(func)?;
+        if let Some(last) = run.chars().next_back() {
+            let next_char_index = self.previously_outputted_index.map_or(0, |i| i + 1);
+            self.previously_outputted_index = Some(next_char_index + run.chars().count() - 1);
+            self.previously_outputted_byte_index =
+                Some(self.next_byte_index + run.len() - last.len_utf8());
+            self.next_byte_index += run.len();
+        }
+        Some(run)
+    }
+
+    /// Consumes whitespace, and comments if they're allowed. Running out of
+    /// input is not an error unless it happens inside a comment.
+    fn skip_whitespace(&mut self) -> Result<(), ParseError> {
+        let newlines = self.skip_whitespace_counting_newlines()?;
+        if newlines > 0 && self.peek().is_some() {
+            let index = self.next_char_index();
+            if let Some(table) = &mut self.newlines {
+                table.insert(index, newlines);
+            }
+        }
+        Ok(())
+    }
+
+    /// Consumes a run of whitespace in one go if the backing iterator
+    /// supports it, see [`CharIterator::take_str_while`], and returns how many
+    /// newlines it contained. Pretty printed input has long runs of
+    /// indentation, which this skips without going through [`Self::peek`]
+    /// for every character.
+    fn skip_whitespace_run(&mut self) -> usize {
+        if self.peeked.is_some() {
+            return 0;
+        }
+        let whitespace = &self.options.whitespace;
+        let Some(run) = self.inner.take_str_while(|c| whitespace.contains(c)) else {
+            return 0;
+        };
+        let Some(last) = run.chars().next_back() else {
+            return 0;
+        };
+        let newlines = run.bytes().filter(|b| *b == b'\n').count();
+        let (chars, bytes, last_len) = (run.chars().count(), run.len(), last.len_utf8());
+        let next_char_index = self.next_char_index();
+        self.previously_outputted_index = Some(next_char_index + chars - 1);
+        self.previously_outputted_byte_index = Some(self.next_byte_index + bytes - last_len);
+        self.next_byte_index += bytes;
+        newlines
+    }
+
+    /// Returns how many newlines were skipped, including those in comments
+    fn skip_whitespace_counting_newlines(&mut self) -> Result<usize, ParseError> {
+        let mut newlines = 0;
+        loop {
+            newlines += self.skip_whitespace_run();
+            if let Some(c) = self.peek().filter(|c| self.options.whitespace.contains(*c)) {
+                self.next_any();
+                newlines += usize::from(c == '\n');
+                continue;
+            }
+            if !self.options.allow_comments || self.next_if(|c| *c == '/').is_none() {
+                return Ok(newlines);
+            }
+            let comment_kind = self.next_any().ok_or(ParseError::UnexpectedEndOfString)?;
+            match comment_kind {
+                '/' => {
+                    if self.next_any_until('\n') {
+                        newlines += 1;
+                    }
+                }
+                '*' => loop {
+                    let c = self.next_any().ok_or(ParseError::UnexpectedEndOfString)?;
+                    newlines += usize::from(c == '\n');
+                    if c == '*' && self.next_if(|c| *c == '/').is_some() {
+                        break;
+                    }
+                },
+                _ => return Err(self.unexpected_character(comment_kind, vec!['/', '*'])),
+            }
+        }
+    }
+
+    /// Consumes whitespace, then returns the next character without consuming it
+    fn peek_non_whitespace(&mut self) -> Result<Option<char>, ParseError> {
+        self.skip_whitespace()?;
+        Ok(self.peek())
+    }
+
+    fn next_non_whitespace(&mut self) -> Result<Option<char>, ParseError> {
+        self.skip_whitespace()?;
+        Ok(self.next_any())
+    }
+
+    /// If the next non-whitespace value is not the expected value,
+    /// whitespace will still be consumed
+    fn next_non_whitespace_if_eq(&mut self, expected: char) -> Result<Option<char>, ParseError> {
+        self.skip_whitespace()?;
+        Ok(self.next_if(|c| *c == expected))
+    }
+
+    /// Called after consuming the opening bracket of an array or object
+    fn enter_nesting(&mut self) -> Result<(), ParseError> {
+        self.depth += 1;
+        if self.options.max_depth.is_some_and(|max| self.depth > max) {
+            return Err(ParseError::MaxDepthExceeded {
+                char_index: self.previous_char_index(),
+                byte_index: self.previous_byte_index(),
+            });
+        }
+        Ok(())
+    }
+
+    fn exit_nesting(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// Called before parsing each value or object member, with whitespace
+    /// already skipped so an error points at its first character
+    fn count_element(&mut self) -> Result<(), ParseError> {
+        self.elements += 1;
+        if self
+            .options
+            .max_elements
+            .is_some_and(|max| self.elements > max)
+        {
+            return Err(ParseError::TooManyElements {
+                char_index: self.next_char_index(),
+                byte_index: self.next_byte_index,
+            });
+        }
+        Ok(())
+    }
+
+    /// Error for the character that was just consumed, spanning only that
+    /// character
+    fn unexpected_character(&self, character: char, expected_characters: Vec<char>) -> ParseError {
+        let char_index = self.previous_char_index();
+        ParseError::UnexpectedCharacter {
+            character,
+            char_index,
+            byte_index: self.previous_byte_index(),
+            expected_characters,
+            span: Span::single(char_index),
+        }
+    }
+
+    fn expect_specific_char(&mut self, expected: char) -> Result<(), ParseError> {
+        let c = self.next_any().ok_or(ParseError::UnexpectedEndOfString)?;
+        if c == expected {
+            Ok(())
+        } else {
+            Err(self.unexpected_character(c, vec![expected]))
+        }
+    }
+
+    /// Consumes the rest of a keyword like `true` after its first character,
+    /// comparing it in one go when the backing iterator supports that, see
+    /// [`CharIterator::skip_prefix`]. Otherwise, or if it doesn't match in
+    /// full, it's consumed character by character to find the error. `rest`
+    /// must be non-empty ASCII.
+    fn expect_keyword_rest(&mut self, rest: &str) -> Result<(), ParseError> {
+        if self.peeked.is_none() && self.inner.skip_prefix(rest) {
+            let next_char_index = self.next_char_index();
+            self.previously_outputted_index = Some(next_char_index + rest.len() - 1);
+            self.previously_outputted_byte_index = Some(self.next_byte_index + rest.len() - 1);
+            self.next_byte_index += rest.len();
+            return Ok(());
+        }
+        for c in rest.chars() {
+            self.expect_specific_char(c)?;
+        }
+        Ok(())
+    }
+
+    /// Consumes the quote that opens a string, which can be a single quote
+    /// if [`ParseOptions::allow_single_quotes`] is on
+    fn expect_opening_quote(&mut self) -> Result<Quote, ParseError> {
+        let c = self
+            .next_non_whitespace()?
+            .ok_or(ParseError::UnexpectedEndOfString)?;
+        if c == '"' || (c == '\'' && self.options.allow_single_quotes) {
+            Ok(Quote {
+                character: c,
+                char_index: self.previous_char_index(),
+                byte_index: self.previous_byte_index(),
+            })
+        } else if self.options.allow_single_quotes {
+            Err(self.unexpected_character(c, vec!['"', '\'']))
+        } else {
+            Err(self.unexpected_character(c, vec!['"']))
+        }
+    }
+
+    fn expect_specific_char_ignore_whitespace(&mut self, expected: char) -> Result<(), ParseError> {
+        let c = self
+            .next_non_whitespace()?
+            .ok_or(ParseError::UnexpectedEndOfString)?;
+        if c == expected {
+            Ok(())
+        } else {
+            Err(self.unexpected_character(c, vec![expected]))
+        }
+    }
+}
+
+/// A range of the input, counted in `char`s, with `end` exclusive
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    fn single(index: usize) -> Self {
+        Self {
+            start: index,
+            end: index + 1,
+        }
+    }
+
+    /// The same range in UTF-8 bytes, for slicing `input`. `None` if it
+    /// doesn't fit in `input`.
+    pub fn byte_range(&self, input: &str) -> Option<core::ops::Range<usize>> {
+        Some(char_to_byte_index(input, self.start)?..char_to_byte_index(input, self.end)?)
+    }
+}
+
+/// Converts a `char_index`, like the ones in errors and [`Span`]s, into the
+/// byte offset of that character in `input`, so the input can be sliced
+/// there. The index just past the last character maps to `input.len()`, and
+/// anything further is `None`.
+pub fn char_to_byte_index(input: &str, char_index: usize) -> Option<usize> {
+    input
+        .char_indices()
+        .map(|(byte_index, _)| byte_index)
+        .chain(core::iter::once(input.len()))
+        .nth(char_index)
+}
+
+/// Where the next character would be consumed from, see
+/// [`Parser::parse_prefix_with_position`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    /// Counted in `char`s from the start of the input
+    pub char_index: usize,
+    /// Offset in UTF-8 bytes, can be used to slice the input
+    pub byte_index: usize,
+}
+
+/// Every error that points at a character carries two indices:
+/// `char_index` counts `char`s from the start of the input, while
+/// `byte_index` is the offset in UTF-8 bytes and can be used to slice the
+/// original `&str`. The two only differ when multibyte characters precede
+/// the error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    UnexpectedCharacter {
+        character: char,
+        char_index: usize,
+        byte_index: usize,
+        expected_characters: Vec<char>,
+        /// The whole token the character is part of where that's meaningful,
+        /// e.g. all of a malformed number, otherwise just the character
+        span: Span,
+    },
+    /// A value was expected, but `found` can't start one. Mismatches within
+    /// a value, like a wrong delimiter in an array, are
+    /// [`ParseError::UnexpectedCharacter`] instead.
+    ExpectedValue {
+        found: char,
+        char_index: usize,
+        byte_index: usize,
+    },
+    /// An object key was expected, but `found` can't start one. Covers both a
+    /// value in place of a key, like `{1: 2}`, and a `}` directly after a `,`
+    /// without [`ParseOptions::allow_trailing_commas`].
+    ExpectedKey {
+        found: char,
+        char_index: usize,
+        byte_index: usize,
+    },
+    UnexpectedEndOfString,
+    /// The input ended inside a string, the indices point at its opening quote
+    UnterminatedString {
+        char_index: usize,
+        byte_index: usize,
+    },
+    /// The input was empty or contained only whitespace
+    EmptyInput,
+    /// A complete value was parsed but more non-whitespace input followed it
+    TrailingCharacter {
+        character: char,
+        char_index: usize,
+        byte_index: usize,
+    },
+    ControlCharacter {
+        control_character: char,
+        char_index: usize,
+        byte_index: usize,
+    },
+    UnexpectedNonHexCharacter {
+        character: char,
+        char_index: usize,
+        byte_index: usize,
+    },
+    /// A `\u` escape that isn't a Unicode scalar value: a high surrogate not
+    /// directly followed by an escaped low surrogate, or a lone low surrogate.
+    /// Points at the backslash of the offending escape.
+    InvalidSurrogate {
+        char_index: usize,
+        byte_index: usize,
+    },
+    /// Points at the opening bracket that went past [`ParseOptions::max_depth`]
+    MaxDepthExceeded {
+        char_index: usize,
+        byte_index: usize,
+    },
+    /// The input to [`parse_bytes`] isn't valid UTF-8, the indices point at the
+    /// first invalid byte
+    InvalidUtf8 {
+        char_index: usize,
+        byte_index: usize,
+    },
+    /// Points at the character that took a string, after unescaping, past
+    /// [`ParseOptions::max_string_length`]
+    StringTooLong {
+        char_index: usize,
+        byte_index: usize,
+    },
+    /// Only returned with [`DuplicateKeyPolicy::Error`], points at the opening
+    /// quote of the repeated key
+    DuplicateKey {
+        key: String,
+        char_index: usize,
+        byte_index: usize,
+    },
+    /// Only returned with [`ParseOptions::reject_imprecise_numbers`], points
+    /// at the start of the number
+    ImpreciseNumber {
+        char_index: usize,
+        byte_index: usize,
+    },
+    /// Points at the start of the value or object member that went past
+    /// [`ParseOptions::max_elements`]
+    TooManyElements {
+        char_index: usize,
+        byte_index: usize,
+    },
+    /// Only returned with [`ParseOptions::max_exponent`], points at the start
+    /// of the number
+    ExponentTooLarge {
+        char_index: usize,
+        byte_index: usize,
+    },
+    /// Reading the input failed, see [`Parser::parse_stream`] and
+    /// [`Parser::parse_file`]. The [`std::io::Error`] itself is reduced to its
+    /// kind and message so errors stay comparable.
+    #[cfg(feature = "std")]
+    Io {
+        kind: std::io::ErrorKind,
+        message: String,
+    },
+    /// The document starts with a kind of value other than the one asked
+    /// for, see [`Parser::parse_object`] and [`Parser::parse_array`]. Only
+    /// the first character is looked at, so the rest may not be valid.
+    UnexpectedTopLevelKind {
+        found: ValueKind,
+    },
+}
+
+impl ParseError {
+    /// What to highlight for this error, `None` for errors that don't point
+    /// at the input
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            ParseError::UnexpectedCharacter { span, .. } => Some(*span),
+            ParseError::UnexpectedEndOfString
+            | ParseError::EmptyInput
+            | ParseError::UnexpectedTopLevelKind { .. } => None,
+            #[cfg(feature = "std")]
+            ParseError::Io { .. } => None,
+            ParseError::ExpectedValue { char_index, .. }
+            | ParseError::ExpectedKey { char_index, .. }
+            | ParseError::UnterminatedString { char_index, .. }
+            | ParseError::TrailingCharacter { char_index, .. }
+            | ParseError::ControlCharacter { char_index, .. }
+            | ParseError::UnexpectedNonHexCharacter { char_index, .. }
+            | ParseError::InvalidSurrogate { char_index, .. }
+            | ParseError::MaxDepthExceeded { char_index, .. }
+            | ParseError::InvalidUtf8 { char_index, .. }
+            | ParseError::StringTooLong { char_index, .. }
+            | ParseError::DuplicateKey { char_index, .. }
+            | ParseError::ImpreciseNumber { char_index, .. }
+            | ParseError::TooManyElements { char_index, .. }
+            | ParseError::ExponentTooLarge { char_index, .. } => Some(Span::single(*char_index)),
+        }
+    }
+}
+
+/// One line in lowercase, positions given as `char` indices
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ParseError::UnexpectedCharacter {
+                character,
+                char_index,
+                expected_characters,
+                ..
+            } => {
+                write!(f, "unexpected {character:?} at character {char_index}")?;
+                for (n, expected) in expected_characters.iter().enumerate() {
+                    let separator = if n == 0 { ", expected one of " } else { ", " };
+                    write!(f, "{separator}{expected:?}")?;
+                }
+                Ok(())
+            }
+            ParseError::ExpectedValue {
+                found, char_index, ..
+            } => write!(
+                f,
+                "expected a value at character {char_index}, found {found:?}"
+            ),
+            ParseError::ExpectedKey {
+                found, char_index, ..
+            } => write!(
+                f,
+                "expected a key at character {char_index}, found {found:?}"
+            ),
+            ParseError::UnexpectedEndOfString => f.write_str("unexpected end of input"),
+            ParseError::UnterminatedString { char_index, .. } => {
+                write!(f, "unterminated string starting at character {char_index}")
+            }
+            ParseError::EmptyInput => f.write_str("empty input"),
+            ParseError::TrailingCharacter {
+                character,
+                char_index,
+                ..
+            } => write!(
+                f,
+                "trailing {character:?} at character {char_index} after the value"
+            ),
+            ParseError::ControlCharacter {
+                control_character,
+                char_index,
+                ..
+            } => write!(
+                f,
+                "unescaped control character {control_character:?} at character {char_index}"
+            ),
+            ParseError::UnexpectedNonHexCharacter {
+                character,
+                char_index,
+                ..
+            } => write!(
+                f,
+                "expected a hex digit at character {char_index}, found {character:?}"
+            ),
+            ParseError::InvalidSurrogate { char_index, .. } => {
+                write!(f, "invalid surrogate escape at character {char_index}")
+            }
+            ParseError::MaxDepthExceeded { char_index, .. } => {
+                write!(f, "nesting too deep at character {char_index}")
+            }
+            ParseError::InvalidUtf8 { byte_index, .. } => {
+                write!(f, "invalid UTF-8 at byte {byte_index}")
+            }
+            ParseError::StringTooLong { char_index, .. } => {
+                write!(f, "string too long at character {char_index}")
+            }
+            ParseError::DuplicateKey {
+                key, char_index, ..
+            } => write!(f, "duplicate key {key:?} at character {char_index}"),
+            ParseError::ImpreciseNumber { char_index, .. } => {
+                write!(f, "imprecise number at character {char_index}")
+            }
+            ParseError::TooManyElements { char_index, .. } => {
+                write!(f, "too many elements at character {char_index}")
+            }
+            ParseError::ExponentTooLarge { char_index, .. } => {
+                write!(f, "exponent too large at character {char_index}")
+            }
+            #[cfg(feature = "std")]
+            ParseError::Io { message, .. } => write!(f, "reading failed: {message}"),
+            ParseError::UnexpectedTopLevelKind { found } => {
+                write!(f, "unexpected top-level {}", found.name())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+trait JsonType<CI: CharIterator>: Sized {
+    fn parse(i: &mut WhitespaceSkippingIndexTrackingIter<CI>) -> Result<Self, ParseError>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum JsonValue {
+    Object(JsonObject),
+    Array(JsonArray),
+    String(JsonString),
+    Number(JsonNumber),
+    /// Only produced with [`ParseOptions::raw_numbers`]. Never equal to a
+    /// [`JsonValue::Number`], even one with the same value.
+    RawNumber(JsonRawNumber),
+    /// Only produced with [`ParseOptions::allow_non_string_keys`], in place of
+    /// every [`JsonValue::Object`]
+    KeyedObject(keyed::JsonKeyedObject),
+    Bool(JsonBool),
+    Null(JsonNull),
+}
+
+/// `null`, so `core::mem::take` leaves a valid value behind
+impl Default for JsonValue {
+    fn default() -> Self {
+        JsonValue::Null(JsonNull)
+    }
+}
+
+/// The type of a [`JsonValue`] without its contents
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+    Object,
+    Array,
+    String,
+    Number,
+    Bool,
+    Null,
+}
+
+impl ValueKind {
+    /// Lowercase name for messages, e.g. `"array"`
+    pub fn name(self) -> &'static str {
+        match self {
+            ValueKind::Object => "object",
+            ValueKind::Array => "array",
+            ValueKind::String => "string",
+            ValueKind::Number => "number",
+            ValueKind::Bool => "bool",
+            ValueKind::Null => "null",
+        }
+    }
+}
+
+impl JsonValue {
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            JsonValue::Object(_) | JsonValue::KeyedObject(_) => ValueKind::Object,
+            JsonValue::Array(_) => ValueKind::Array,
+            JsonValue::String(_) => ValueKind::String,
+            JsonValue::Number(_) | JsonValue::RawNumber(_) => ValueKind::Number,
+            JsonValue::Bool(_) => ValueKind::Bool,
+            JsonValue::Null(_) => ValueKind::Null,
+        }
+    }
+
+    /// Lowercase name of the variant, e.g. `"array"`
+    pub fn type_name(&self) -> &'static str {
+        self.kind().name()
+    }
+
+    /// Number of elements or members, `None` if this isn't an array or object
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            JsonValue::Array(array) => Some(array.len()),
+            JsonValue::Object(object) => Some(object.len()),
+            JsonValue::KeyedObject(object) => Some(object.len()),
+            _ => None,
+        }
+    }
+
+    /// `None` if this isn't an array or object
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// [`JsonObject::get_case_insensitive`], `None` if this isn't an object
+    pub fn get_case_insensitive(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(object) => object.get_case_insensitive(key),
+            _ => None,
+        }
+    }
+
+    /// Every value stored under `key` in any object in the tree, in
+    /// depth-first order. Only string keys can match. A match is listed
+    /// before any matches nested inside it.
+    pub fn find_all(&self, key: &str) -> Vec<&JsonValue> {
+        fn collect<'a>(value: &'a JsonValue, key: &str, found: &mut Vec<&'a JsonValue>) {
+            match value {
+                JsonValue::Object(object) => {
+                    for (k, v) in object.iter() {
+                        if k.0 == key {
+                            found.push(v);
+                        }
+                        collect(v, key, found);
+                    }
+                }
+                JsonValue::KeyedObject(object) => {
+                    for (k, v) in object.iter() {
+                        if matches!(&k.0, JsonValue::String(k) if k.0 == key) {
+                            found.push(v);
+                        }
+                        collect(v, key, found);
+                    }
+                }
+                JsonValue::Array(array) => {
+                    for v in array {
+                        collect(v, key, found);
+                    }
+                }
+                _ => {}
+            }
+        }
+        let mut found = Vec::new();
+        collect(self, key, &mut found);
+        found
+    }
+
+    /// Calls `f` on every string, number, bool and null in the tree, in
+    /// depth-first order. Object keys aren't passed to `f`. `f` may replace a
+    /// scalar with a container, which isn't visited.
+    pub fn map_scalars<F: FnMut(&mut JsonValue)>(&mut self, f: &mut F) {
+        match self {
+            JsonValue::Object(object) => {
+                for (_, value) in object.0.iter_mut() {
+                    value.map_scalars(f);
+                }
+            }
+            JsonValue::KeyedObject(object) => {
+                for (_, value) in object.0.iter_mut() {
+                    value.map_scalars(f);
+                }
+            }
+            JsonValue::Array(array) => {
+                for value in array.0.iter_mut() {
+                    value.map_scalars(f);
+                }
+            }
+            _ => f(self),
+        }
+    }
+}
+
+impl<CI: CharIterator> JsonType<CI> for JsonValue {
+    fn parse(i: &mut WhitespaceSkippingIndexTrackingIter<CI>) -> Result<Self, ParseError> {
+        i.peek_non_whitespace()?
+            .ok_or(ParseError::UnexpectedEndOfString)?;
+        i.count_element()?;
+        JsonValue::parse_uncounted(i)
+    }
+}
+
+impl JsonValue {
+    /// [`JsonValue::parse`] without counting towards
+    /// [`ParseOptions::max_elements`], for object keys, which only count as
+    /// part of their member whatever kind of value they are
+    fn parse_uncounted<CI: CharIterator>(
+        i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    ) -> Result<Self, ParseError> {
+        let next_char = i
+            .peek_non_whitespace()?
+            .ok_or(ParseError::UnexpectedEndOfString)?;
+        match next_char {
+            '{' if i.options.allow_non_string_keys => {
+                Ok(JsonValue::KeyedObject(keyed::JsonKeyedObject::parse(i)?))
+            }
+            '{' => Ok(JsonValue::Object(JsonObject::parse(i)?)),
+            '[' => Ok(JsonValue::Array(JsonArray::parse(i)?)),
+            '"' => Ok(JsonValue::String(JsonString::parse(i)?)),
+            '\'' if i.options.allow_single_quotes => Ok(JsonValue::String(JsonString::parse(i)?)),
+            c if i.options.starts_number(c) && i.options.raw_numbers => {
+                Ok(JsonValue::RawNumber(JsonRawNumber::parse(i)?))
+            }
+            c if i.options.starts_number(c) => Ok(JsonValue::Number(JsonNumber::parse(i)?)),
+            't' | 'f' => Ok(JsonValue::Bool(JsonBool::parse(i)?)),
+            'n' => Ok(JsonValue::Null(JsonNull::parse(i)?)),
+            _ => Err(unexpected_value_start(i)),
+        }
+    }
+}
+
+/// Consumes the peeked character that can't start a value and builds the
+/// error for it
+fn unexpected_value_start<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+) -> ParseError {
+    let Some(found) = i.next_any() else {
+        return ParseError::UnexpectedEndOfString;
+    };
+    ParseError::ExpectedValue {
+        found,
+        char_index: i.previous_char_index(),
+        byte_index: i.previous_byte_index(),
+    }
+}
+
+/// Opt-in deviations from strict JSON, and resource limits. The default is
+/// strict JSON, limited only to a nesting depth of [`DEFAULT_MAX_DEPTH`].
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+    /// `// line` and `/* block */` comments wherever whitespace is allowed
+    pub allow_comments: bool,
+    /// A single `,` directly before a closing `]` or `}`
+    pub allow_trailing_commas: bool,
+    /// Maximum number of nested arrays and objects, [`DEFAULT_MAX_DEPTH`] by
+    /// default, `None` for unlimited. Parsing recurses once per level, so
+    /// without a limit deep enough nesting overflows the stack.
+    pub max_depth: Option<usize>,
+    /// What happens when a key repeats within an object
+    pub duplicate_key_policy: DuplicateKeyPolicy,
+    /// Maximum length in bytes of a string or key after unescaping, `None`
+    /// for unlimited
+    pub max_string_length: Option<usize>,
+    /// JSON5 style object keys written as identifiers, e.g. `{name: 1}`.
+    /// They start with a letter, `_` or `$`, followed by letters, digits, `_`
+    /// or `$`.
+    pub allow_unquoted_keys: bool,
+    /// JSON5 style `'single quoted'` strings and keys. A `"` inside them
+    /// doesn't need escaping, and `\'` is accepted as an escape.
+    pub allow_single_quotes: bool,
+    /// Keep numbers as their source text in a [`JsonRawNumber`] instead of
+    /// converting them to `f64`, so no precision is lost
+    pub raw_numbers: bool,
+    /// Keep each number's source text next to its `f64` in the
+    /// [`JsonNumber`], so it's written back out as it was, e.g. `1.50` rather
+    /// than `1.5`. Ignored when [`ParseOptions::raw_numbers`] is on.
+    pub preserve_number_text: bool,
+    /// Unrecognized escapes like `\x` are kept as they were written,
+    /// backslash included, instead of being an error. A backslash right
+    /// before the closing quote still escapes it, and a malformed `\u`
+    /// escape is still an error.
+    pub lenient_escapes: bool,
+    /// Out of spec: object keys can be any scalar, not just strings. Objects
+    /// are then parsed as [`JsonValue::KeyedObject`]s.
+    pub allow_non_string_keys: bool,
+    /// Numbers whose decimal value isn't exactly an `f64`, like `0.1` or
+    /// 2^53 + 1, are a [`ParseError::ImpreciseNumber`] instead of being
+    /// rounded. Doesn't apply to [`ParseOptions::raw_numbers`], which are
+    /// never rounded.
+    pub reject_imprecise_numbers: bool,
+    /// Maximum number of values and object members in the whole document,
+    /// counting containers themselves as well as what they contain. Keys
+    /// only count as part of their member, whatever kind of value they are.
+    /// `None` for unlimited.
+    pub max_elements: Option<usize>,
+    /// Each `\u` escape that would be a [`ParseError::InvalidSurrogate`]
+    /// becomes a U+FFFD instead, like a lossy UTF-16 decode
+    pub replace_invalid_surrogates: bool,
+    /// The characters skipped between tokens, [`JSON_WHITESPACE`] by default.
+    /// Adding e.g. a form feed or vertical tab accepts inputs that use them as
+    /// separators, string contents are unaffected. Characters that can be part
+    /// of a token mustn't be included.
+    pub whitespace: WhitespaceSet,
+    /// Numbers whose exponent, as written, is larger in magnitude than this
+    /// are a [`ParseError::ExponentTooLarge`], e.g. `1e400` with a limit of
+    /// 308. `None` for unlimited, where out of range numbers become infinity
+    /// or zero. Applies to [`ParseOptions::raw_numbers`] too.
+    pub max_exponent: Option<u64>,
+    /// JSON5 style `NaN`, `Infinity` and `-Infinity` as numbers. They're
+    /// still written as `null`, unless their text was kept with
+    /// [`ParseOptions::preserve_number_text`] or
+    /// [`ParseOptions::raw_numbers`].
+    pub allow_non_finite_numbers: bool,
+}
+
+/// Nesting limit of the default [`ParseOptions`], deep enough for any
+/// sensible document, like `serde_json`'s
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            allow_comments: false,
+            allow_trailing_commas: false,
+            max_depth: Some(DEFAULT_MAX_DEPTH),
+            duplicate_key_policy: DuplicateKeyPolicy::LastWins,
+            max_string_length: None,
+            allow_unquoted_keys: false,
+            allow_single_quotes: false,
+            raw_numbers: false,
+            preserve_number_text: false,
+            lenient_escapes: false,
+            allow_non_string_keys: false,
+            reject_imprecise_numbers: false,
+            max_elements: None,
+            replace_invalid_surrogates: false,
+            whitespace: WhitespaceSet::new(&JSON_WHITESPACE),
+            max_exponent: None,
+            allow_non_finite_numbers: false,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Whether `c` can be the first character of a number
+    fn starts_number(&self, c: char) -> bool {
+        c == '-' || c.is_ascii_digit() || (self.allow_non_finite_numbers && matches!(c, 'N' | 'I'))
+    }
+
+    /// The kind of value that starts with `c`, `None` if no value can
+    fn kind_starting_with(&self, c: char) -> Option<ValueKind> {
+        match c {
+            '{' => Some(ValueKind::Object),
+            '[' => Some(ValueKind::Array),
+            '"' => Some(ValueKind::String),
+            '\'' if self.allow_single_quotes => Some(ValueKind::String),
+            't' | 'f' => Some(ValueKind::Bool),
+            'n' => Some(ValueKind::Null),
+            c if self.starts_number(c) => Some(ValueKind::Number),
+            _ => None,
+        }
+    }
+}
+
+/// What happens when a key repeats within an object, see
+/// [`ParseOptions::duplicate_key_policy`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateKeyPolicy {
+    /// The later value replaces the earlier one, which keeps its position
+    #[default]
+    LastWins,
+    /// The repeat is a [`ParseError::DuplicateKey`]
+    Error,
+    /// If both values are objects, the later one's members are added to the
+    /// earlier one, with repeats among them handled the same way. Otherwise
+    /// the later value wins, arrays included.
+    Merge,
+}
+
+/// Parses documents according to its [`ParseOptions`], which are set with the
+/// builder methods
+#[derive(Clone, Debug, Default)]
+pub struct Parser {
+    options: ParseOptions,
+}
+
+/// Starts from `options` instead of the defaults, the builder methods can
+/// still change them
+impl From<ParseOptions> for Parser {
+    fn from(options: ParseOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl Parser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn allow_comments(mut self, allow: bool) -> Self {
+        self.options.allow_comments = allow;
+        self
+    }
+
+    pub fn allow_trailing_commas(mut self, allow: bool) -> Self {
+        self.options.allow_trailing_commas = allow;
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.options.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Removes the nesting limit, for documents known to be deeper than
+    /// [`DEFAULT_MAX_DEPTH`] that are run on a large enough stack
+    pub fn unlimited_depth(mut self) -> Self {
+        self.options.max_depth = None;
+        self
+    }
+
+    pub fn duplicate_key_policy(mut self, policy: DuplicateKeyPolicy) -> Self {
+        self.options.duplicate_key_policy = policy;
+        self
+    }
+
+    /// Shorthand for [`DuplicateKeyPolicy::LastWins`], or
+    /// [`DuplicateKeyPolicy::Error`] if `allow` is false
+    pub fn allow_duplicate_keys(self, allow: bool) -> Self {
+        self.duplicate_key_policy(if allow {
+            DuplicateKeyPolicy::LastWins
+        } else {
+            DuplicateKeyPolicy::Error
+        })
+    }
+
+    pub fn max_string_length(mut self, max_string_length: usize) -> Self {
+        self.options.max_string_length = Some(max_string_length);
+        self
+    }
+
+    pub fn allow_unquoted_keys(mut self, allow: bool) -> Self {
+        self.options.allow_unquoted_keys = allow;
+        self
+    }
+
+    pub fn allow_single_quotes(mut self, allow: bool) -> Self {
+        self.options.allow_single_quotes = allow;
+        self
+    }
+
+    pub fn raw_numbers(mut self, raw: bool) -> Self {
+        self.options.raw_numbers = raw;
+        self
+    }
+
+    pub fn preserve_number_text(mut self, preserve: bool) -> Self {
+        self.options.preserve_number_text = preserve;
+        self
+    }
+
+    pub fn lenient_escapes(mut self, lenient: bool) -> Self {
+        self.options.lenient_escapes = lenient;
+        self
+    }
+
+    pub fn allow_non_string_keys(mut self, allow: bool) -> Self {
+        self.options.allow_non_string_keys = allow;
+        self
+    }
+
+    pub fn reject_imprecise_numbers(mut self, reject: bool) -> Self {
+        self.options.reject_imprecise_numbers = reject;
+        self
+    }
+
+    pub fn max_elements(mut self, max_elements: usize) -> Self {
+        self.options.max_elements = Some(max_elements);
+        self
+    }
+
+    pub fn max_exponent(mut self, max_exponent: u64) -> Self {
+        self.options.max_exponent = Some(max_exponent);
+        self
+    }
+
+    pub fn replace_invalid_surrogates(mut self, replace: bool) -> Self {
+        self.options.replace_invalid_surrogates = replace;
+        self
+    }
+
+    pub fn allow_non_finite_numbers(mut self, allow: bool) -> Self {
+        self.options.allow_non_finite_numbers = allow;
+        self
+    }
+
+    /// Replaces the whole set, see [`ParseOptions::whitespace`]
+    pub fn whitespace(mut self, whitespace: &[char]) -> Self {
+        self.options.whitespace = WhitespaceSet::new(whitespace);
+        self
+    }
+
+    /// Parses a complete JSON document. Leading and trailing whitespace is
+    /// allowed, anything else after the value is an error. A single leading
+    /// UTF-8 byte order mark is skipped, it still counts towards error indices.
+    pub fn parse(&self, input: &str) -> Result<JsonValue, ParseError> {
+        self.parse_document(input.chars())
+    }
+
+    /// Same as [`Parser::parse`], but scanning UTF-8 bytes directly instead of
+    /// decoding every `char`. Output and errors are identical, invalid UTF-8 is
+    /// reported as [`ParseError::InvalidUtf8`].
+    pub fn parse_bytes(&self, input: &[u8]) -> Result<JsonValue, ParseError> {
+        self.parse_document(bytes::Utf8ByteIter::new(input)?)
+    }
+
+    /// Same as [`Parser::parse`], for characters coming from any iterator,
+    /// e.g. a decoder or an adapter chain. `byte_index`es in errors are what
+    /// the offsets would be in the characters' UTF-8 encoding.
+    pub fn parse_chars<I: Iterator<Item = char>>(&self, chars: I) -> Result<JsonValue, ParseError> {
+        self.parse_document(IterChars(chars))
+    }
+
+    /// [`Parser::parse`] for documents that must be an object, anything else
+    /// is a [`ParseError::UnexpectedTopLevelKind`]. With
+    /// [`ParseOptions::allow_non_string_keys`], nested objects are
+    /// [`JsonValue::KeyedObject`]s as usual, but the top-level one is a
+    /// [`JsonObject`], so its own keys still have to be strings.
+    pub fn parse_object(&self, input: &str) -> Result<JsonObject, ParseError> {
+        self.parse_top_level(input, ValueKind::Object, JsonObject::parse)
+    }
+
+    /// [`Parser::parse`] for documents that must be an array, anything else
+    /// is a [`ParseError::UnexpectedTopLevelKind`]
+    pub fn parse_array(&self, input: &str) -> Result<JsonArray, ParseError> {
+        self.parse_top_level(input, ValueKind::Array, JsonArray::parse)
+    }
+
+    /// Parses a document with `parse_value`, after checking from its first
+    /// character that the value is of the `expected` kind, so nothing is
+    /// parsed only to be thrown away
+    fn parse_top_level<'a, T>(
+        &self,
+        input: &'a str,
+        expected: ValueKind,
+        parse_value: impl FnOnce(
+            &mut WhitespaceSkippingIndexTrackingIter<core::str::Chars<'a>>,
+        ) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        self.parse_document_with(input.chars(), |i| {
+            let next_char = i
+                .peek_non_whitespace()?
+                .ok_or(ParseError::UnexpectedEndOfString)?;
+            match i.options.kind_starting_with(next_char) {
+                Some(found) if found != expected => {
+                    Err(ParseError::UnexpectedTopLevelKind { found })
+                }
+                Some(_) => {
+                    i.count_element()?;
+                    parse_value(i)
+                }
+                None => Err(unexpected_value_start(i)),
+            }
+        })
+    }
+
+    /// Parses the value at the start of `input` and returns it along with
+    /// the byte offset right after it. Anything after the value, including
+    /// whitespace, is left alone, so `&input[offset..]` can be parsed next.
+    pub fn parse_prefix(&self, input: &str) -> Result<(JsonValue, usize), ParseError> {
+        let (value, position) = self.parse_prefix_with_position(input);
+        Ok((value?, position.byte_index))
+    }
+
+    /// [`Parser::parse_prefix`], along with where parsing stopped whether it
+    /// succeeded or not, for building errors or further parsing on top. After
+    /// a value that's right after it, after an error it's usually right after
+    /// the offending character.
+    pub fn parse_prefix_with_position(
+        &self,
+        input: &str,
+    ) -> (Result<JsonValue, ParseError>, Position) {
+        let mut i =
+            WhitespaceSkippingIndexTrackingIter::with_options(input.chars(), self.options.clone());
+        let value = start_document(&mut i).and_then(|()| JsonValue::parse(&mut i));
+        (value, i.position())
+    }
+
+    fn parse_document<CI: CharIterator>(&self, ci: CI) -> Result<JsonValue, ParseError> {
+        self.parse_document_with(ci, JsonValue::parse)
+    }
+
+    /// Handles everything around the top-level value: the byte order mark,
+    /// empty input and trailing characters
+    fn parse_document_with<CI: CharIterator, T>(
+        &self,
+        ci: CI,
+        parse_value: impl FnOnce(&mut WhitespaceSkippingIndexTrackingIter<CI>) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        let mut i = WhitespaceSkippingIndexTrackingIter::with_options(ci, self.options.clone());
+        parse_document_in(&mut i, parse_value)
+    }
+}
+
+/// Skips a byte order mark and checks there's something to parse
+fn start_document<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+) -> Result<(), ParseError> {
+    i.next_if(|c| *c == '\u{FEFF}');
+    if i.peek_non_whitespace()?.is_none() {
+        return Err(ParseError::EmptyInput);
+    }
+    Ok(())
+}
+
+/// [`Parser::parse_document_with`] for an iterator that was already set up
+fn parse_document_in<CI: CharIterator, T>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    parse_value: impl FnOnce(&mut WhitespaceSkippingIndexTrackingIter<CI>) -> Result<T, ParseError>,
+) -> Result<T, ParseError> {
+    start_document(i)?;
+    let value = parse_value(i)?;
+    if let Some(c) = i.next_non_whitespace()? {
+        return Err(ParseError::TrailingCharacter {
+            character: c,
+            char_index: i.previous_char_index(),
+            byte_index: i.previous_byte_index(),
+        });
+    }
+    Ok(value)
+}
+
+/// Parses a complete JSON document with the default [`Parser`]
+pub fn parse(input: &str) -> Result<JsonValue, ParseError> {
+    Parser::new().parse(input)
+}
+
+/// [`Parser::parse_chars`] with the default [`Parser`]
+pub fn parse_chars<I: Iterator<Item = char>>(chars: I) -> Result<JsonValue, ParseError> {
+    Parser::new().parse_chars(chars)
+}
+
+/// [`Parser::parse_prefix`] with the default [`Parser`]
+pub fn parse_prefix(input: &str) -> Result<(JsonValue, usize), ParseError> {
+    Parser::new().parse_prefix(input)
+}
+
+/// Parses a complete UTF-8 encoded JSON document with the default [`Parser`]
+pub fn parse_bytes(input: &[u8]) -> Result<JsonValue, ParseError> {
+    Parser::new().parse_bytes(input)
+}
+
+/// [`Parser::parse_object`] with the default [`Parser`]
+pub fn parse_object(input: &str) -> Result<JsonObject, ParseError> {
+    Parser::new().parse_object(input)
+}
+
+/// [`Parser::parse_array`] with the default [`Parser`]
+pub fn parse_array(input: &str) -> Result<JsonArray, ParseError> {
+    Parser::new().parse_array(input)
+}
+
+/// Parses untrusted bytes as a UTF-8 JSON document. Same as [`parse_bytes`],
+/// whose default [`ParseOptions::max_depth`] of [`DEFAULT_MAX_DEPTH`] means
+/// every input gives `Ok` or `Err` rather than overflowing the stack.
+pub fn try_parse(input: &[u8]) -> Result<JsonValue, ParseError> {
+    parse_bytes(input)
+}
+
+/// A [`ParseError`] from [`parse_lines`] along with the line it occurred on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineError {
+    /// 1-based line number
+    pub line: usize,
+    /// Indices are relative to the start of the line
+    pub error: ParseError,
+}
+
+/// Parses newline-delimited JSON (JSON Lines), where every non-blank line is
+/// a complete JSON document
+pub fn parse_lines(input: &str) -> impl Iterator<Item = Result<JsonValue, LineError>> + '_ {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.chars().all(is_json_whitespace))
+        .map(|(index, line)| {
+            parse(line).map_err(|error| LineError {
+                line: index + 1,
+                error,
+            })
+        })
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct JsonArray(pub Vec<JsonValue>);
+impl JsonArray {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, JsonValue> {
+        self.0.iter()
+    }
+}
+
+impl IntoIterator for JsonArray {
+    type Item = JsonValue;
+    type IntoIter = alloc::vec::IntoIter<JsonValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a JsonArray {
+    type Item = &'a JsonValue;
+    type IntoIter = core::slice::Iter<'a, JsonValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<CI: CharIterator> JsonType<CI> for JsonArray {
+    fn parse(i: &mut WhitespaceSkippingIndexTrackingIter<CI>) -> Result<Self, ParseError> {
+        parse_array_with(i, JsonValue::parse).map(JsonArray)
+    }
+}
+
+/// Parses `[`, comma separated elements and `]`, honoring the nesting and
+/// trailing comma options. Shared by every value representation, which only
+/// differ in how they parse an element.
+fn parse_array_with<CI: CharIterator, T>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    mut parse_element: impl FnMut(&mut WhitespaceSkippingIndexTrackingIter<CI>) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    i.expect_specific_char_ignore_whitespace('[')?;
+    i.enter_nesting()?;
+    let mut v = Vec::new();
+    let is_empty = i.next_non_whitespace_if_eq(']')?.is_some();
+    if is_empty {
+        i.exit_nesting();
+        return Ok(v);
+    }
+    loop {
+        v.push(parse_element(i)?);
+        let next_char = i
+            .next_non_whitespace()?
+            .ok_or(ParseError::UnexpectedEndOfString)?;
+        if next_char == ']' {
+            i.exit_nesting();
+            return Ok(v);
+        } else if next_char == ',' {
+            if i.options.allow_trailing_commas && i.next_non_whitespace_if_eq(']')?.is_some() {
+                i.exit_nesting();
+                return Ok(v);
+            }
+            continue;
+        } else {
+            return Err(i.unexpected_character(next_char, vec![']', ',']));
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct JsonBool(pub bool);
+impl<CI: CharIterator> JsonType<CI> for JsonBool {
+    fn parse(i: &mut WhitespaceSkippingIndexTrackingIter<CI>) -> Result<Self, ParseError> {
+        let first_char = i
+            .next_non_whitespace()?
+            .ok_or(ParseError::UnexpectedEndOfString)?;
+        let (rest, value) = match first_char {
+            't' => ("rue", true),
+            'f' => ("alse", false),
+            _ => return Err(i.unexpected_character(first_char, vec!['t', 'f'])),
+        };
+        i.expect_keyword_rest(rest)?;
+        Ok(JsonBool(value))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct JsonNull;
+impl<CI: CharIterator> JsonType<CI> for JsonNull {
+    fn parse(i: &mut WhitespaceSkippingIndexTrackingIter<CI>) -> Result<Self, ParseError> {
+        i.expect_specific_char_ignore_whitespace('n')?;
+        i.expect_keyword_rest("ull")?;
+        Ok(JsonNull)
+    }
+}
+
+/// The value, and its source text if it was parsed with
+/// [`ParseOptions::preserve_number_text`]. Read the text through
+/// [`JsonNumber::source_text`], which drops it once it no longer matches the
+/// value.
+#[derive(Clone)]
+pub struct JsonNumber(f64, Option<Box<str>>);
+
+/// Only the values are compared, `1.50` and `1.5` are equal
+impl PartialEq for JsonNumber {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+/// The parser only produces NaN with
+/// [`ParseOptions::allow_non_finite_numbers`], and it's the only `f64` that
+/// isn't equal to itself. A NaN breaks reflexivity, so it shouldn't be used in
+/// a `HashSet` or as a `HashMap` key.
+impl Eq for JsonNumber {}
+
+/// The source text is only shown when it was kept
+impl core::fmt::Debug for JsonNumber {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut tuple = f.debug_tuple("JsonNumber");
+        tuple.field(&self.0);
+        if let Some(text) = self.source_text() {
+            tuple.field(&text);
+        }
+        tuple.finish()
+    }
+}
+
+/// Hashes the bit pattern, with `-0.0` normalized to `0.0` since the two
+/// compare equal
+impl core::hash::Hash for JsonNumber {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        let normalized = if self.0 == 0.0 { 0.0 } else { self.0 };
+        normalized.to_bits().hash(state);
+    }
+}
+
+/// Characters that can appear somewhere in a number
+fn is_number_char(c: char) -> bool {
+    c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')
+}
+
+impl JsonNumber {
+    pub fn as_f64(&self) -> f64 {
+        self.0
+    }
+
+    /// Interprets the number as an `i64` if it's an integer in range.
+    ///
+    /// Integers are stored as the nearest `f64`, so beyond 2^53 they may have
+    /// been rounded while parsing, and `i64::MAX` itself rounds up to 2^63,
+    /// which is out of range. Use [`ParseOptions::raw_numbers`] to keep such
+    /// integers exact.
+    pub fn as_i64(&self) -> Option<i64> {
+        // -2^63 and 2^63 are exact in f64, unlike i64::MAX
+        let in_range =
+            (-9_223_372_036_854_775_808.0..9_223_372_036_854_775_808.0).contains(&self.0);
+        // In range the cast truncates, so only integers survive the round trip
+        // (`f64::fract` needs `std`)
+        let integer = self.0 as i64;
+        (in_range && integer as f64 == self.0).then_some(integer)
+    }
+
+    /// Like [`JsonNumber::as_i64`], for non-negative integers below 2^64
+    pub fn as_u64(&self) -> Option<u64> {
+        let in_range = (0.0..18_446_744_073_709_551_616.0).contains(&self.0);
+        let integer = self.0 as u64;
+        (in_range && integer as f64 == self.0).then_some(integer)
+    }
+
+    /// The number exactly as it was written, see
+    /// [`ParseOptions::preserve_number_text`]. Once the value has been changed
+    /// the text is stale and left out.
+    pub fn source_text(&self) -> Option<&str> {
+        let text = self.1.as_deref()?;
+        let written: f64 = text.parse().ok()?;
+        let same = written.to_bits() == self.0.to_bits() || (written.is_nan() && self.0.is_nan());
+        same.then_some(text)
+    }
+}
+
+impl<CI: CharIterator> JsonType<CI> for JsonNumber {
+    fn parse(i: &mut WhitespaceSkippingIndexTrackingIter<CI>) -> Result<Self, ParseError> {
+        let mut text = i.take_scratch();
+        parse_number_token(i, &mut text)?;
+        // The JSON number grammar is a subset of what f64's FromStr accepts.
+        // Out of range values round to the nearest f64, or to infinity.
+        let number = text.parse().unwrap();
+        check_precision(i, &text, number)?;
+        let source = i.options.preserve_number_text.then(|| text.as_str().into());
+        i.return_scratch(text);
+        Ok(JsonNumber(number, source))
+    }
+}
+
+/// A number kept as its validated source text, see
+/// [`ParseOptions::raw_numbers`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct JsonRawNumber(String);
+
+impl JsonRawNumber {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The nearest `f64`, which may lose precision
+    pub fn as_f64(&self) -> f64 {
+        self.0.parse().unwrap()
+    }
+
+    /// Exact, `None` for fractions, exponents and out of range integers
+    pub fn as_i64(&self) -> Option<i64> {
+        self.0.parse().ok()
+    }
+
+    /// Exact, `None` for fractions, exponents and out of range integers
+    pub fn as_u64(&self) -> Option<u64> {
+        self.0.parse().ok()
+    }
+}
+
+impl<CI: CharIterator> JsonType<CI> for JsonRawNumber {
+    fn parse(i: &mut WhitespaceSkippingIndexTrackingIter<CI>) -> Result<Self, ParseError> {
+        let mut text = String::new();
+        parse_number_token(i, &mut text)?;
+        Ok(JsonRawNumber(text))
+    }
+}
+
+/// The significant digits of a decimal number, without leading or trailing
+/// zeros, and the position of the decimal point relative to the first of them.
+/// Accepts JSON numbers and Rust's `{:e}` output. Zero has no digits.
+fn significant_digits(text: &str) -> (Vec<u8>, i64) {
+    let text = text.strip_prefix('-').unwrap_or(text);
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(e) => {
+            let exponent = &text[e + 1..];
+            // Far enough out of range either way, without overflowing below
+            let saturated = if exponent.starts_with('-') {
+                i64::MIN / 2
+            } else {
+                i64::MAX / 2
+            };
+            (&text[..e], exponent.parse().unwrap_or(saturated))
+        }
+        None => (text, 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let mut digits = [integer.as_bytes(), fraction.as_bytes()].concat();
+    let leading_zeros = digits.iter().take_while(|d| **d == b'0').count();
+    if leading_zeros == digits.len() {
+        return (Vec::new(), 0);
+    }
+    digits.drain(..leading_zeros);
+    while digits.last() == Some(&b'0') {
+        digits.pop();
+    }
+    (
+        digits,
+        integer.len() as i64 + exponent - leading_zeros as i64,
+    )
+}
+
+/// With [`ParseOptions::reject_imprecise_numbers`] on, checks that `number`
+/// is exactly the decimal value of the just consumed `text`
+fn check_precision<CI: CharIterator>(
+    i: &WhitespaceSkippingIndexTrackingIter<CI>,
+    text: &str,
+    number: f64,
+) -> Result<(), ParseError> {
+    if !i.options.reject_imprecise_numbers
+        || matches!(text.trim_start_matches('-'), "NaN" | "Infinity")
+    {
+        return Ok(());
+    }
+    // Every finite f64 has a finite decimal expansion of at most 767
+    // significant digits, which this precision writes out in full
+    if !number.is_finite()
+        || significant_digits(text) != significant_digits(&format!("{number:.800e}"))
+    {
+        // Numbers are ASCII, so `text.len()` is also its length in chars
+        return Err(ParseError::ImpreciseNumber {
+            char_index: i.next_char_index() - text.len(),
+            byte_index: i.next_byte_index - text.len(),
+        });
+    }
+    Ok(())
+}
+
+/// With [`ParseOptions::max_exponent`] set, checks the exponent of the just
+/// consumed number `text`
+fn check_exponent<CI: CharIterator>(
+    i: &WhitespaceSkippingIndexTrackingIter<CI>,
+    text: &str,
+) -> Result<(), ParseError> {
+    let Some(max) = i.options.max_exponent else {
+        return Ok(());
+    };
+    let Some(e) = text.find(['e', 'E']) else {
+        return Ok(());
+    };
+    let magnitude =
+        text[e + 1..]
+            .bytes()
+            .filter(u8::is_ascii_digit)
+            .fold(0u64, |magnitude, digit| {
+                magnitude
+                    .saturating_mul(10)
+                    .saturating_add(u64::from(digit - b'0'))
+            });
+    if magnitude > max {
+        // Numbers are ASCII, so `text.len()` is also its length in chars
+        return Err(ParseError::ExponentTooLarge {
+            char_index: i.next_char_index() - text.len(),
+            byte_index: i.next_byte_index - text.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Consumes a number after skipping whitespace, pushing its text onto `text`
+fn parse_number_token<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    text: &mut String,
+) -> Result<(), ParseError> {
+    /// Consumes one or more digits, pushing them onto `text`
+    fn parse_digits<CI: CharIterator>(
+        i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+        text: &mut String,
+    ) -> Result<(), ParseError> {
+        let first_digit = i.next_any().ok_or(ParseError::UnexpectedEndOfString)?;
+        if !first_digit.is_ascii_digit() {
+            return Err(i.unexpected_character(first_digit, ('0'..='9').collect()));
+        }
+        text.push(first_digit);
+        while let Some(digit) = i.next_if(char::is_ascii_digit) {
+            text.push(digit);
+        }
+        Ok(())
+    }
+
+    /// Consumes the number, from the optional minus sign to the end of the
+    /// optional exponent, pushing it onto `text`
+    fn parse_number_text<CI: CharIterator>(
+        i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+        text: &mut String,
+    ) -> Result<(), ParseError> {
+        let minus = i.next_if(|c| *c == '-');
+        if let Some(minus) = minus {
+            text.push(minus);
+        }
+        if i.options.allow_non_finite_numbers {
+            let keyword = match i.peek() {
+                Some('I') => Some("Infinity"),
+                Some('N') if minus.is_none() => Some("NaN"),
+                _ => None,
+            };
+            if let Some(keyword) = keyword {
+                i.next_any();
+                i.expect_keyword_rest(&keyword[1..])?;
+                text.push_str(keyword);
+                return Ok(());
+            }
+        }
+        if let Some(zero) = i.next_if(|c| *c == '0') {
+            text.push(zero);
+            // An integer part is either a lone 0 or doesn't start with 0
+            if let Some(digit) = i.next_if(char::is_ascii_digit) {
+                return Err(i.unexpected_character(digit, vec!['.', 'e', 'E']));
+            }
+        } else {
+            parse_digits(i, text)?;
+        }
+        if let Some(dot) = i.next_if(|c| *c == '.') {
+            text.push(dot);
+            parse_digits(i, text)?;
+        }
+        if let Some(e) = i.next_if(|c| *c == 'e' || *c == 'E') {
+            text.push(e);
+            if let Some(sign) = i.next_if(|c| *c == '+' || *c == '-') {
+                text.push(sign);
+            }
+            parse_digits(i, text)?;
+        }
+        Ok(())
+    }
+
+    let first_char = i
+        .peek_non_whitespace()?
+        .ok_or(ParseError::UnexpectedEndOfString)?;
+    if !i.options.starts_number(first_char) {
+        i.next_any();
+        let mut expected_characters = vec!['-'];
+        expected_characters.extend('0'..='9');
+        return Err(i.unexpected_character(first_char, expected_characters));
+    }
+    let start = i.next_char_index();
+    let text_start = text.len();
+    if let Err(mut error) = parse_number_text(i, text) {
+        if let ParseError::UnexpectedCharacter {
+            character,
+            char_index,
+            span,
+            ..
+        } = &mut error
+        {
+            // Cover the rest of a malformed token too, e.g. all of `1..2`,
+            // but not a character that ends it, e.g. the `]` of `[1e]`
+            let end = if is_number_char(*character) {
+                while i.next_if(|c| is_number_char(*c)).is_some() {}
+                i.next_char_index()
+            } else {
+                *char_index
+            };
+            *span = Span { start, end };
+        }
+        return Err(error);
+    }
+    check_exponent(i, &text[text_start..])
+}
+
+/// Members are kept in insertion order. Keys are unique, inserting an existing
+/// key replaces its value in place. Lookups are a linear scan, parsing and
+/// comparing large objects go through a hashed index instead.
+#[derive(Clone, Default)]
+pub struct JsonObject(Vec<(JsonString, JsonValue)>);
+impl JsonObject {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        self.0.iter().position(|(k, _)| k.0 == key)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        self.0.iter().find(|(k, _)| k.0 == key).map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut JsonValue> {
+        self.0.iter_mut().find(|(k, _)| k.0 == key).map(|(_, v)| v)
+    }
+
+    /// Like [`JsonObject::get`], but ignoring ASCII case. If several keys
+    /// differ only in case, e.g. `Name` and `name`, the one that comes first
+    /// in the object wins, even if a later one matches `key` exactly.
+    pub fn get_case_insensitive(&self, key: &str) -> Option<&JsonValue> {
+        self.0
+            .iter()
+            .find(|(k, _)| k.0.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    /// Returns the previous value if `key` was already present, in which case
+    /// the member keeps its original position
+    pub fn insert(&mut self, key: JsonString, value: JsonValue) -> Option<JsonValue> {
+        match self.0.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => Some(core::mem::replace(existing, value)),
+            None => {
+                self.0.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Members after the removed one keep their relative order
+    pub fn remove(&mut self, key: &str) -> Option<JsonValue> {
+        let index = self.position(key)?;
+        Some(self.0.remove(index).1)
+    }
+
+    pub fn entry(&mut self, key: String) -> Entry<'_> {
+        match self.position(&key) {
+            Some(index) => Entry::Occupied {
+                object: self,
+                index,
+            },
+            None => Entry::Vacant { object: self, key },
+        }
+    }
+
+    /// `entry(key.to_string()).or_insert_with(default)`, without allocating
+    /// the key when it's already present
+    pub fn get_or_insert_with(
+        &mut self,
+        key: &str,
+        default: impl FnOnce() -> JsonValue,
+    ) -> &mut JsonValue {
+        let index = match self.position(key) {
+            Some(index) => index,
+            None => {
+                self.0.push((JsonString(key.to_string()), default()));
+                self.0.len() - 1
+            }
+        };
+        &mut self.0[index].1
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&JsonString, &JsonValue)> {
+        self.0.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn keys(&self) -> impl ExactSizeIterator<Item = &JsonString> {
+        self.0.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl ExactSizeIterator<Item = &JsonValue> {
+        self.0.iter().map(|(_, v)| v)
+    }
+
+    pub fn values_mut(&mut self) -> impl ExactSizeIterator<Item = &mut JsonValue> {
+        self.0.iter_mut().map(|(_, v)| v)
+    }
+}
+
+/// A view into a single member of a [`JsonObject`], from [`JsonObject::entry`]
+pub enum Entry<'a> {
+    Occupied {
+        object: &'a mut JsonObject,
+        index: usize,
+    },
+    Vacant {
+        object: &'a mut JsonObject,
+        key: String,
+    },
+}
+
+impl<'a> Entry<'a> {
+    pub fn key(&self) -> &str {
+        match self {
+            Entry::Occupied { object, index } => &object.0[*index].0 .0,
+            Entry::Vacant { key, .. } => key,
+        }
+    }
+
+    /// Appends `default` to the end of the object if the key is missing
+    pub fn or_insert(self, default: JsonValue) -> &'a mut JsonValue {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with(self, default: impl FnOnce() -> JsonValue) -> &'a mut JsonValue {
+        match self {
+            Entry::Occupied { object, index } => &mut object.0[index].1,
+            Entry::Vacant { object, key } => {
+                object.0.push((JsonString(key), default()));
+                &mut object.0.last_mut().unwrap().1
+            }
+        }
+    }
+}
+
+/// Formats like a map, `{"key": value}`
+impl core::fmt::Debug for JsonObject {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Member order doesn't affect equality
+impl PartialEq for JsonObject {
+    fn eq(&self, other: &Self) -> bool {
+        let mut index = members::KeyIndex::default();
+        self.len() == other.len()
+            && self.0.iter().all(|(k, v)| {
+                index
+                    .get(&other.0, k)
+                    .is_some_and(|(_, other_v)| v == other_v)
+            })
+    }
+}
+
+impl Eq for JsonObject {}
+
+/// Consistent with `PartialEq`, member order doesn't affect the hash
+impl core::hash::Hash for JsonObject {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        hash_members(&self.0, state);
+    }
+}
+
+/// Hashes an object's members without depending on their order. Each member
+/// is hashed on its own and the results are combined with a wrapping sum.
+fn hash_members<M: core::hash::Hash, H: core::hash::Hasher>(members: &[M], state: &mut H) {
+    use core::hash::{BuildHasher, BuildHasherDefault, Hash};
+    let build_hasher = BuildHasherDefault::<Fnv1a>::default();
+    let combined = members
+        .iter()
+        .map(|member| build_hasher.hash_one(member))
+        .fold(0u64, u64::wrapping_add);
+    members.len().hash(state);
+    combined.hash(state);
+}
+
+/// The 64-bit FNV-1a hash, for [`hash_members`]. It needs the same hash for
+/// equal members every time, which rules out a randomly seeded hasher, and
+/// `core` has no fixed one.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl core::hash::Hasher for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl IntoIterator for JsonObject {
+    type Item = (JsonString, JsonValue);
+    type IntoIter = alloc::vec::IntoIter<(JsonString, JsonValue)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl members::Member for (JsonString, JsonValue) {
+    type Key = JsonString;
+
+    fn key(&self) -> &JsonString {
+        &self.0
+    }
+
+    fn object_members(&mut self) -> Option<&mut Vec<Self>> {
+        match &mut self.1 {
+            JsonValue::Object(object) => Some(&mut object.0),
+            _ => None,
+        }
+    }
+}
+
+impl<CI: CharIterator> JsonType<CI> for JsonObject {
+    fn parse(i: &mut WhitespaceSkippingIndexTrackingIter<CI>) -> Result<Self, ParseError> {
+        let mut object = JsonObject(Vec::new());
+        let mut index = members::KeyIndex::default();
+        parse_object_with(i, |i| {
+            let key_char_index = i.next_char_index();
+            let key_byte_index = i.next_byte_index;
+            let key = JsonString::parse_key(i)?;
+            let policy = i.options.duplicate_key_policy;
+            if policy == DuplicateKeyPolicy::Error && index.position(&object.0, &key).is_some() {
+                return Err(ParseError::DuplicateKey {
+                    key: key.0,
+                    char_index: key_char_index,
+                    byte_index: key_byte_index,
+                });
+            }
+            i.expect_specific_char_ignore_whitespace(':')?;
+            let value = JsonValue::parse(i)?;
+            members::insert_member(&mut object.0, &mut index, (key, value), policy);
+            Ok(())
+        })?;
+        Ok(object)
+    }
+}
+
+/// Parses `{`, comma separated members and `}`, honoring the nesting and
+/// trailing comma options. `parse_member` is called with whitespace already
+/// skipped, and is responsible for the key, the `:` and the value.
+fn parse_object_with<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    mut parse_member: impl FnMut(&mut WhitespaceSkippingIndexTrackingIter<CI>) -> Result<(), ParseError>,
+) -> Result<(), ParseError> {
+    i.expect_specific_char_ignore_whitespace('{')?;
+    i.enter_nesting()?;
+    let is_empty = i.next_non_whitespace_if_eq('}')?.is_some();
+    if is_empty {
+        i.exit_nesting();
+        return Ok(());
+    }
+    loop {
+        i.skip_whitespace()?;
+        i.count_element()?;
+        parse_member(i)?;
+        let next_char = i
+            .next_non_whitespace()?
+            .ok_or(ParseError::UnexpectedEndOfString)?;
+        if next_char == '}' {
+            i.exit_nesting();
+            return Ok(());
+        } else if next_char == ',' {
+            if i.options.allow_trailing_commas && i.next_non_whitespace_if_eq('}')?.is_some() {
+                i.exit_nesting();
+                return Ok(());
+            }
+            continue;
+        } else {
+            return Err(i.unexpected_character(next_char, vec!['}', ',']));
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct JsonString(pub String);
+impl<CI: CharIterator> JsonType<CI> for JsonString {
+    fn parse(i: &mut WhitespaceSkippingIndexTrackingIter<CI>) -> Result<Self, ParseError> {
+        let quote = i.expect_opening_quote()?;
+        let mut string = i.take_scratch();
+        Self::parse_rest(i, &mut string, quote)?;
+        // Cloning allocates exactly once, at the final length
+        let out = JsonString(string.clone());
+        i.return_scratch(string);
+        Ok(out)
+    }
+}
+
+/// Lets maps keyed by [`JsonString`] be looked up with a `&str`
+impl core::borrow::Borrow<str> for JsonString {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+/// The quote a string was opened with, and where
+#[derive(Clone, Copy)]
+struct Quote {
+    character: char,
+    char_index: usize,
+    byte_index: usize,
+}
+
+/// Whether `c` can start an unquoted key, see
+/// [`ParseOptions::allow_unquoted_keys`]
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+fn is_identifier_char(c: char) -> bool {
+    is_identifier_start(c) || c.is_alphanumeric()
+}
+
+/// Checks that the next non-whitespace character can start a key, so a
+/// misplaced value like the `1` in `{1: 2}` is reported as a missing key
+/// rather than as a missing quote
+fn expect_key_start<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+) -> Result<(), ParseError> {
+    let c = i
+        .peek_non_whitespace()?
+        .ok_or(ParseError::UnexpectedEndOfString)?;
+    if c == '"' || (c == '\'' && i.options.allow_single_quotes) || next_key_is_unquoted(i)? {
+        return Ok(());
+    }
+    i.next_any();
+    Err(ParseError::ExpectedKey {
+        found: c,
+        char_index: i.previous_char_index(),
+        byte_index: i.previous_byte_index(),
+    })
+}
+
+/// Whether the next key is unquoted, in which case it's parsed with
+/// [`parse_identifier`] rather than as a string
+fn next_key_is_unquoted<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+) -> Result<bool, ParseError> {
+    Ok(i.options.allow_unquoted_keys && i.peek_non_whitespace()?.is_some_and(is_identifier_start))
+}
+
+/// Consumes an unquoted key, handing each character to `push`. Keys count
+/// towards [`ParseOptions::max_string_length`] like quoted ones.
+fn parse_identifier<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    mut push: impl FnMut(char),
+) -> Result<(), ParseError> {
+    let mut len = 0;
+    while let Some(c) = i.next_if(|c| is_identifier_char(*c)) {
+        len += c.len_utf8();
+        if i.options.max_string_length.is_some_and(|max| len > max) {
+            return Err(ParseError::StringTooLong {
+                char_index: i.previous_char_index(),
+                byte_index: i.previous_byte_index(),
+            });
+        }
+        push(c);
+    }
+    Ok(())
+}
+
+/// Characters that can be copied as they are into a string delimited by
+/// `quote`
+fn is_unescaped_string_char(c: char, quote: char) -> bool {
+    c != quote && c != '\\' && !c.is_control()
+}
+
+/// Errors if appending `run` to a string that's already `string_len` bytes
+/// long goes past [`ParseOptions::max_string_length`], pointing at the same
+/// character a char by char copy would have
+fn check_run_length(
+    max_string_length: Option<usize>,
+    string_len: usize,
+    run: &str,
+    run_char_index: usize,
+    run_byte_index: usize,
+) -> Result<(), ParseError> {
+    let Some(max) = max_string_length else {
+        return Ok(());
+    };
+    let remaining = max.saturating_sub(string_len);
+    match run
+        .char_indices()
+        .find(|(offset, c)| offset + c.len_utf8() > remaining)
+    {
+        Some((offset, _)) => Err(ParseError::StringTooLong {
+            char_index: run_char_index + run[..offset].chars().count(),
+            byte_index: run_byte_index + offset,
+        }),
+        None => Ok(()),
+    }
+}
+
+impl JsonString {
+    /// Parses an object key, which is a string unless
+    /// [`ParseOptions::allow_unquoted_keys`] allows an identifier
+    fn parse_key<CI: CharIterator>(
+        i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    ) -> Result<Self, ParseError> {
+        expect_key_start(i)?;
+        if next_key_is_unquoted(i)? {
+            let mut key = String::new();
+            parse_identifier(i, |c| key.push(c))?;
+            return Ok(JsonString(key));
+        }
+        Self::parse(i)
+    }
+
+    /// Parses the rest of a string whose opening `quote` was already
+    /// consumed, up to and including the matching closing quote, appending
+    /// the unescaped contents to `string`
+    fn parse_rest<CI: CharIterator>(
+        i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+        string: &mut String,
+        quote: Quote,
+    ) -> Result<(), ParseError> {
+        Self::parse_contents(i, string, quote.character).map_err(|error| match error {
+            ParseError::UnexpectedEndOfString => ParseError::UnterminatedString {
+                char_index: quote.char_index,
+                byte_index: quote.byte_index,
+            },
+            error => error,
+        })
+    }
+
+    fn parse_contents<CI: CharIterator>(
+        i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+        string: &mut String,
+        quote: char,
+    ) -> Result<(), ParseError> {
+        let max_string_length = i.options.max_string_length;
+        loop {
+            let run_char_index = i.next_char_index();
+            let run_byte_index = i.next_byte_index;
+            if let Some(run) = i.take_str_while(|c| is_unescaped_string_char(c, quote)) {
+                check_run_length(
+                    max_string_length,
+                    string.len(),
+                    run,
+                    run_char_index,
+                    run_byte_index,
+                )?;
+                string.push_str(run);
+            }
+            let next_char = i.next_any().ok_or(ParseError::UnexpectedEndOfString)?;
+            if next_char == quote {
+                return Ok(());
+            } else if next_char == '\\' {
+                let escaped_character = i.next_any().ok_or(ParseError::UnexpectedEndOfString)?;
+                Self::parse_escape(i, string, escaped_character)?;
+            } else if next_char.is_control() {
+                return Err(ParseError::ControlCharacter {
+                    control_character: next_char,
+                    char_index: i.previous_char_index(),
+                    byte_index: i.previous_byte_index(),
+                });
+            } else {
+                string.push(next_char);
+            }
+            if i.options
+                .max_string_length
+                .is_some_and(|max| string.len() > max)
+            {
+                return Err(ParseError::StringTooLong {
+                    char_index: i.previous_char_index(),
+                    byte_index: i.previous_byte_index(),
+                });
+            }
+        }
+    }
+
+    /// Appends what `\escaped_character` stands for, the backslash and the
+    /// character having been consumed
+    fn parse_escape<CI: CharIterator>(
+        i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+        string: &mut String,
+        escaped_character: char,
+    ) -> Result<(), ParseError> {
+        if escaped_character == '"' {
+            string.push('"');
+        } else if escaped_character == '\'' && i.options.allow_single_quotes {
+            string.push('\'');
+        } else if escaped_character == '\\' {
+            string.push('\\');
+        } else if escaped_character == '/' {
+            string.push('/');
+        } else if escaped_character == 'b' {
+            string.push('\u{8}');
+        } else if escaped_character == 'f' {
+            string.push('\u{c}');
+        } else if escaped_character == 'n' {
+            string.push('\n');
+        } else if escaped_character == 'r' {
+            string.push('\r');
+        } else if escaped_character == 't' {
+            string.push('\t');
+        } else if escaped_character == 'u' {
+            Self::parse_unicode_escape(i, string)?;
+        } else if i.options.lenient_escapes && !escaped_character.is_control() {
+            string.push('\\');
+            string.push(escaped_character);
+        } else {
+            let mut expected_characters = vec!['"', '\\', '/', 'b', 'f', 'n', 'r', 't', 'u'];
+            if i.options.allow_single_quotes {
+                expected_characters.push('\'');
+            }
+            return Err(i.unexpected_character(escaped_character, expected_characters));
+        }
+        Ok(())
+    }
+
+    /// Appends the character a `\u` escape stands for, the `\u` having been
+    /// consumed. A high surrogate must be directly followed by an escaped low
+    /// surrogate, and a low surrogate can't stand alone. Anything else is a
+    /// [`ParseError::InvalidSurrogate`], or a U+FFFD with
+    /// [`ParseOptions::replace_invalid_surrogates`].
+    fn parse_unicode_escape<CI: CharIterator>(
+        i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+        string: &mut String,
+    ) -> Result<(), ParseError> {
+        // The backslash is the single byte before the `u`
+        let mut escape_char_index = i.previous_char_index() - 1;
+        let mut escape_byte_index = i.previous_byte_index() - 1;
+        let mut unit = parse4hex(i)?;
+        loop {
+            let invalid = ParseError::InvalidSurrogate {
+                char_index: escape_char_index,
+                byte_index: escape_byte_index,
+            };
+            if !(0xD800..=0xDBFF).contains(&unit) {
+                return match char::from_u32(unit.into()) {
+                    Some(c) => {
+                        string.push(c);
+                        Ok(())
+                    }
+                    None => Self::invalid_surrogate(i, string, invalid),
+                };
+            }
+            match i.peek() {
+                Some('\\') => i.next_any(),
+                Some(_) => return Self::invalid_surrogate(i, string, invalid),
+                None => return Err(ParseError::UnexpectedEndOfString),
+            };
+            let next_escape_char_index = i.previous_char_index();
+            let next_escape_byte_index = i.previous_byte_index();
+            let escaped_character = i.next_any().ok_or(ParseError::UnexpectedEndOfString)?;
+            if escaped_character != 'u' {
+                Self::invalid_surrogate(i, string, invalid)?;
+                return Self::parse_escape(i, string, escaped_character);
+            }
+            let next_unit = parse4hex(i)?;
+            if (0xDC00..=0xDFFF).contains(&next_unit) {
+                let code_point =
+                    0x10000 + ((u32::from(unit) - 0xD800) << 10) + (u32::from(next_unit) - 0xDC00);
+                return match char::from_u32(code_point) {
+                    Some(c) => {
+                        string.push(c);
+                        Ok(())
+                    }
+                    None => Self::invalid_surrogate(i, string, invalid),
+                };
+            }
+            // The high surrogate is unpaired, but the next escape may still
+            // start a valid pair of its own
+            Self::invalid_surrogate(i, string, invalid)?;
+            unit = next_unit;
+            escape_char_index = next_escape_char_index;
+            escape_byte_index = next_escape_byte_index;
+        }
+    }
+
+    /// Either `error`, or a U+FFFD in its place with
+    /// [`ParseOptions::replace_invalid_surrogates`]
+    fn invalid_surrogate<CI: CharIterator>(
+        i: &WhitespaceSkippingIndexTrackingIter<CI>,
+        string: &mut String,
+        error: ParseError,
+    ) -> Result<(), ParseError> {
+        if i.options.replace_invalid_surrogates {
+            string.push(char::REPLACEMENT_CHARACTER);
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+}
+
+/// Reads the four hex digits of a `\u` escape
+fn parse4hex<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+) -> Result<u16, ParseError> {
+    let mut next_char = i.next_any().ok_or(ParseError::UnexpectedEndOfString)?;
+    let b00 = hex_digit_to_byte(next_char);
+    let b00 = b00.ok_or(ParseError::UnexpectedNonHexCharacter {
+        character: next_char,
+        char_index: i.previous_char_index(),
+        byte_index: i.previous_byte_index(),
+    })?;
+    next_char = i.next_any().ok_or(ParseError::UnexpectedEndOfString)?;
+    let b01 = hex_digit_to_byte(next_char);
+    let b01 = b01.ok_or(ParseError::UnexpectedNonHexCharacter {
+        character: next_char,
+        char_index: i.previous_char_index(),
+        byte_index: i.previous_byte_index(),
+    })?;
+
+    next_char = i.next_any().ok_or(ParseError::UnexpectedEndOfString)?;
+    let b10 = hex_digit_to_byte(next_char);
+    let b10 = b10.ok_or(ParseError::UnexpectedNonHexCharacter {
+        character: next_char,
+        char_index: i.previous_char_index(),
+        byte_index: i.previous_byte_index(),
+    })?;
+    next_char = i.next_any().ok_or(ParseError::UnexpectedEndOfString)?;
+    let b11 = hex_digit_to_byte(next_char);
+    let b11 = b11.ok_or(ParseError::UnexpectedNonHexCharacter {
+        character: next_char,
+        char_index: i.previous_char_index(),
+        byte_index: i.previous_byte_index(),
+    })?;
+
+    let b0 = (b00 << 4) | b01;
+    let b1 = (b10 << 4) | b11;
+
+    Ok(((b0 as u16) << 8) | (b1 as u16))
+}
+
+fn hex_digit_to_byte(hex_digit: char) -> Option<u8> {
+    match hex_digit {
+        '0' => Some(0),
+        '1' => Some(1),
+        '2' => Some(2),
+        '3' => Some(3),
+        '4' => Some(4),
+        '5' => Some(5),
+        '6' => Some(6),
+        '7' => Some(7),
+        '8' => Some(8),
+        '9' => Some(9),
+        'a' | 'A' => Some(0xA),
+        'b' | 'B' => Some(0xB),
+        'c' | 'C' => Some(0xC),
+        'd' | 'D' => Some(0xD),
+        'e' | 'E' => Some(0xE),
+        'f' | 'F' => Some(0xF),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts allocations made by the current thread, so tests running in
+    /// parallel don't affect each other
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+            ALLOCATIONS.with(|a| a.set(a.get() + 1));
+            std::alloc::System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+            std::alloc::System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(
+            &self,
+            ptr: *mut u8,
+            layout: std::alloc::Layout,
+            new_size: usize,
+        ) -> *mut u8 {
+            ALLOCATIONS.with(|a| a.set(a.get() + 1));
+            std::alloc::System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAllocator = CountingAllocator;
+
+    fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+        let before = ALLOCATIONS.with(|a| a.get());
+        let out = f();
+        (out, ALLOCATIONS.with(|a| a.get()) - before)
+    }
+
+    #[test]
+    fn all_non_surrogates_are_valid() {
+        fn test(x: u16) {
+            let mut iter = char::decode_utf16(std::iter::once(x));
+            assert!(iter.next().unwrap().is_ok());
+            let second_next = iter.next();
+            assert!(second_next.is_none());
+        }
+        for x in 0x0000..=0xD7FF {
+            test(x)
+        }
+        for x in 0xE000..=0xFFFF {
+            test(x)
+        }
+    }
+
+    #[test]
+    fn error_reports_char_and_byte_index() {
+        let mut i = WhitespaceSkippingIndexTrackingIter::new("\"é\u{1}\"".chars());
+        let result = JsonString::parse(&mut i);
+        assert_eq!(
+            result,
+            Err(ParseError::ControlCharacter {
+                control_character: '\u{1}',
+                char_index: 2,
+                byte_index: 3
+            })
+        );
+    }
+
+    #[test]
+    fn control_characters_in_strings() {
+        for (raw, escape) in [('\n', "\\n"), ('\r', "\\r"), ('\t', "\\t")] {
+            let input = format!("[\"ok\",\n \"é{raw}\"]");
+            let expected = ParseError::ControlCharacter {
+                control_character: raw,
+                char_index: 10,
+                byte_index: 11,
+            };
+            assert_eq!(parse(&input).unwrap_err(), expected);
+            assert_eq!(parse_bytes(input.as_bytes()).unwrap_err(), expected);
+
+            let escaped = format!("[\"ok\",\n \"é{escape}\"]");
+            assert_eq!(parse(&escaped).unwrap(), json!(["ok", format!("é{raw}")]));
+        }
+        // Only the first control character counts
+        assert_eq!(
+            parse("\"\u{0}\u{1f}\""),
+            Err(ParseError::ControlCharacter {
+                control_character: '\0',
+                char_index: 1,
+                byte_index: 1
+            })
+        );
+    }
+
+    #[test]
+    fn unterminated_string_points_at_opening_quote() {
+        assert_eq!(
+            parse("\"abc").unwrap_err(),
+            ParseError::UnterminatedString {
+                char_index: 0,
+                byte_index: 0,
+            }
+        );
+        for input in [
+            r#"{"é": "ab"#,
+            r#"{"é": "ab\"#,
+            r#"{"é": "ab\u00"#,
+            r#"{"é": "\ud83d"#,
+        ] {
+            assert_eq!(
+                parse(input).unwrap_err(),
+                ParseError::UnterminatedString {
+                    char_index: 6,
+                    byte_index: 7,
+                },
+                "{input}"
+            );
+        }
+        assert_eq!(
+            parse(r#"{"ab"#).unwrap_err(),
+            ParseError::UnterminatedString {
+                char_index: 1,
+                byte_index: 1,
+            }
+        );
+        assert_eq!(
+            parse(r#"["ab""#).unwrap_err(),
+            ParseError::UnexpectedEndOfString
+        );
+    }
+
+    #[test]
+    fn parses_nested_document() {
+        let value = parse(r#" {"a": [1, -2.5e3, true, false, null], "b": "c"} "#);
+        let Ok(JsonValue::Object(object)) = value else {
+            panic!("expected an object");
+        };
+        let Some(JsonValue::Array(JsonArray(array))) = object.get("a") else {
+            panic!("expected an array under \"a\"");
+        };
+        assert_eq!(array.len(), 5);
+        assert!(matches!(array[1], JsonValue::Number(JsonNumber(n, _)) if n == -2500.0));
+        assert!(matches!(
+            object.get("b"),
+            Some(JsonValue::String(JsonString(s))) if s == "c"
+        ));
+    }
+
+    #[test]
+    fn empty_input_is_distinct_from_truncated_input() {
+        assert_eq!(parse(""), Err(ParseError::EmptyInput));
+        assert_eq!(parse("   \n\t"), Err(ParseError::EmptyInput));
+        assert_eq!(parse("[1,"), Err(ParseError::UnexpectedEndOfString));
+    }
+
+    #[test]
+    fn container_len_and_is_empty() {
+        let empty_array = json!([]);
+        let array = json!([1, 2, 3]);
+        let empty_object = json!({});
+        let object = json!({"a": 1});
+        assert_eq!(empty_array.len(), Some(0));
+        assert_eq!(empty_array.is_empty(), Some(true));
+        assert_eq!(array.len(), Some(3));
+        assert_eq!(array.is_empty(), Some(false));
+        assert_eq!(empty_object.len(), Some(0));
+        assert_eq!(empty_object.is_empty(), Some(true));
+        assert_eq!(object.len(), Some(1));
+        assert_eq!(object.is_empty(), Some(false));
+        assert_eq!(json!("abc").len(), None);
+        assert_eq!(json!(null).is_empty(), None);
+    }
+
+    #[test]
+    fn defaults_are_null_and_empty() {
+        assert_eq!(JsonValue::default(), json!(null));
+        assert_eq!(JsonArray::default(), JsonArray(Vec::new()));
+        assert!(JsonObject::default().is_empty());
+
+        let mut value = json!({"a": [1]});
+        let taken = std::mem::take(&mut value);
+        assert_eq!(value, json!(null));
+        assert_eq!(taken, json!({"a": [1]}));
+    }
+
+    #[test]
+    fn find_all_in_depth_first_order() {
+        let value = json!({
+            "id": 1,
+            "children": [
+                {"id": 2, "children": []},
+                {"name": "x", "child": {"id": {"id": 3}}}
+            ],
+            "meta": {"id": 4}
+        });
+        assert_eq!(
+            value.find_all("id"),
+            [
+                &json!(1),
+                &json!(2),
+                &json!({"id": 3}),
+                &json!(3),
+                &json!(4)
+            ]
+        );
+        assert!(value.find_all("missing").is_empty());
+        assert!(json!("id").find_all("id").is_empty());
+    }
+
+    #[test]
+    fn map_scalars_uppercases_strings() {
+        let mut value = json!({"name": "ab", "tags": ["x", {"y": "z"}, 1, null], "ok": true});
+        let mut visited = 0;
+        value.map_scalars(&mut |scalar| {
+            visited += 1;
+            if let JsonValue::String(s) = scalar {
+                s.0 = s.0.to_uppercase();
+            }
+        });
+        assert_eq!(visited, 6);
+        assert_eq!(
+            value,
+            json!({"name": "AB", "tags": ["X", {"y": "Z"}, 1, null], "ok": true})
+        );
+
+        let mut scalar = json!(1.25);
+        scalar.map_scalars(&mut |n| *n = json!(["wrapped"]));
+        assert_eq!(scalar, json!(["wrapped"]));
+    }
+
+    #[test]
+    fn case_insensitive_lookup() {
+        let value = json!({"Name": 1, "name": 2, "ID": 3, "ß": 4});
+        assert_eq!(value.get_case_insensitive("name"), Some(&json!(1)));
+        assert_eq!(value.get_case_insensitive("NAME"), Some(&json!(1)));
+        assert_eq!(value.get_case_insensitive("id"), Some(&json!(3)));
+        // Only ASCII letters are folded
+        assert_eq!(value.get_case_insensitive("SS"), None);
+        assert_eq!(value.get_case_insensitive("missing"), None);
+        assert_eq!(json!([1]).get_case_insensitive("0"), None);
+
+        let value = json!({"name": 2, "Name": 1});
+        assert_eq!(value.get_case_insensitive("Name"), Some(&json!(2)));
+    }
+
+    #[test]
+    fn iterate_array() {
+        let Ok(JsonValue::Array(array)) = parse("[1, \"a\", null]") else {
+            panic!("expected an array");
+        };
+        assert_eq!(array.iter().count(), 3);
+        let mut borrowed = Vec::new();
+        for item in &array {
+            borrowed.push(item.to_string());
+        }
+        assert_eq!(borrowed, ["1", "\"a\"", "null"]);
+        let items: Vec<JsonValue> = array.into_iter().collect();
+        assert!(items == vec![json!(1), json!("a"), json!(null)]);
+    }
+
+    #[test]
+    fn iterate_object_in_insertion_order() {
+        let Ok(JsonValue::Object(object)) = parse(r#"{"z": 1, "a": 2, "m": 3}"#) else {
+            panic!("expected an object");
+        };
+        let keys: Vec<&str> = object.iter().map(|(k, _)| k.0.as_str()).collect();
+        assert_eq!(keys, ["z", "a", "m"]);
+        let mut members = Vec::new();
+        for (k, v) in object {
+            members.push((k.0, v));
+        }
+        assert_eq!(members.len(), 3);
+        assert!(members[1].0 == "a" && members[1].1 == json!(2));
+    }
+
+    #[test]
+    fn object_keys_and_values() {
+        let Ok(JsonValue::Object(mut object)) = parse(r#"{"z": 1, "a": [2], "m": null}"#) else {
+            panic!("expected an object");
+        };
+        let keys: Vec<&str> = object.keys().map(|k| k.0.as_str()).collect();
+        assert_eq!(keys, ["z", "a", "m"]);
+        let values: Vec<&JsonValue> = object.values().collect();
+        assert!(values == [&json!(1), &json!([2]), &json!(null)]);
+        for value in object.values_mut() {
+            *value = JsonValue::Array(JsonArray(vec![std::mem::take(value)]));
+        }
+        assert_eq!(
+            JsonValue::Object(object),
+            json!({"z": [1], "a": [[2]], "m": [null]})
+        );
+    }
+
+    #[test]
+    fn leading_byte_order_mark_is_skipped() {
+        assert!(matches!(parse("\u{FEFF}{}"), Ok(JsonValue::Object(o)) if o.is_empty()));
+        assert_eq!(
+            parse("\u{FEFF}\u{FEFF}{}"),
+            Err(ParseError::ExpectedValue {
+                found: '\u{FEFF}',
+                char_index: 1,
+                byte_index: 3
+            })
+        );
+        assert_eq!(
+            parse("[\u{FEFF}]"),
+            Err(ParseError::ExpectedValue {
+                found: '\u{FEFF}',
+                char_index: 1,
+                byte_index: 1
+            })
+        );
+    }
+
+    #[test]
+    fn parse_json_lines() {
+        let input = "{\"a\": 1}\n\n[true] false\r\n  \"ok\"  \n";
+        let results: Vec<_> = parse_lines(input).collect();
+        assert_eq!(results.len(), 3);
+        assert!(matches!(&results[0], Ok(v) if *v == json!({"a": 1})));
+        assert_eq!(
+            results[1],
+            Err(LineError {
+                line: 3,
+                error: ParseError::TrailingCharacter {
+                    character: 'f',
+                    char_index: 7,
+                    byte_index: 7
+                }
+            })
+        );
+        assert!(matches!(&results[2], Ok(v) if *v == json!("ok")));
+    }
+
+    #[test]
+    fn duplicate_key_policies() {
+        let input = r#"{"a":{"x":1},"a":{"y":2}}"#;
+        let parse_with = |policy| Parser::new().duplicate_key_policy(policy).parse(input);
+        assert_eq!(
+            parse_with(DuplicateKeyPolicy::LastWins),
+            Ok(json!({"a": {"y": 2}}))
+        );
+        assert_eq!(
+            parse_with(DuplicateKeyPolicy::Error),
+            Err(ParseError::DuplicateKey {
+                key: "a".to_string(),
+                char_index: 13,
+                byte_index: 13
+            })
+        );
+        assert_eq!(
+            parse_with(DuplicateKeyPolicy::Merge),
+            Ok(json!({"a": {"x": 1, "y": 2}}))
+        );
+
+        let merge = Parser::new().duplicate_key_policy(DuplicateKeyPolicy::Merge);
+        assert_eq!(
+            merge.parse(r#"{"a": {"b": {"c": 1, "d": [1]}, "e": 1}, "f": 1, "a": {"b": {"d": [2]}, "e": {}}}"#),
+            Ok(json!({"a": {"b": {"c": 1, "d": [2]}, "e": {}}, "f": 1}))
+        );
+    }
+
+    #[test]
+    fn parser_options_take_effect() {
+        let parser = Parser::new()
+            .allow_comments(true)
+            .allow_trailing_commas(true)
+            .max_depth(2)
+            .allow_duplicate_keys(false);
+        let input = "// header\n{\"a\": [1, 2, /* inline */ 3,], \"b\": null,}";
+        assert!(matches!(parser.parse(input), Ok(v) if v == json!({"a": [1, 2, 3], "b": null})));
+        assert_eq!(
+            parse(input),
+            Err(ParseError::ExpectedValue {
+                found: '/',
+                char_index: 0,
+                byte_index: 0
+            })
+        );
+
+        assert_eq!(
+            parser.parse("[[[]]]"),
+            Err(ParseError::MaxDepthExceeded {
+                char_index: 2,
+                byte_index: 2
+            })
+        );
+        assert!(parse("[[[]]]").is_ok());
+
+        assert_eq!(
+            parser.parse(r#"{"k": 1, "k": 2}"#),
+            Err(ParseError::DuplicateKey {
+                key: "k".to_string(),
+                char_index: 9,
+                byte_index: 9
+            })
+        );
+        assert!(matches!(parse(r#"{"k": 1, "k": 2}"#), Ok(v) if v == json!({"k": 2})));
+    }
+
+    #[test]
+    fn default_max_depth() {
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(parse(&nested(DEFAULT_MAX_DEPTH)).is_ok());
+        assert_eq!(
+            parse(&nested(DEFAULT_MAX_DEPTH + 1)),
+            Err(ParseError::MaxDepthExceeded {
+                char_index: DEFAULT_MAX_DEPTH,
+                byte_index: DEFAULT_MAX_DEPTH
+            })
+        );
+        assert!(Parser::new()
+            .unlimited_depth()
+            .parse(&nested(DEFAULT_MAX_DEPTH * 2))
+            .is_ok());
+    }
+
+    #[test]
+    fn non_finite_numbers() {
+        let parser = Parser::new().allow_non_finite_numbers(true);
+        let value = parser.parse("[NaN, Infinity, -Infinity, -1]").unwrap();
+        let JsonValue::Array(array) = &value else {
+            panic!("expected an array");
+        };
+        let numbers: Vec<f64> = array
+            .0
+            .iter()
+            .map(|n| match n {
+                JsonValue::Number(n) => n.0,
+                _ => panic!("expected a number"),
+            })
+            .collect();
+        assert!(numbers[0].is_nan());
+        assert_eq!(numbers[1..], [f64::INFINITY, f64::NEG_INFINITY, -1.0]);
+        assert_eq!(value.to_string(), "[null,null,null,-1]");
+        assert_eq!(
+            parser
+                .clone()
+                .raw_numbers(true)
+                .parse("[-Infinity]")
+                .unwrap()
+                .to_string(),
+            "[-Infinity]"
+        );
+        assert!(parser
+            .clone()
+            .reject_imprecise_numbers(true)
+            .parse("Infinity")
+            .is_ok());
+
+        assert_eq!(
+            parse("NaN"),
+            Err(ParseError::ExpectedValue {
+                found: 'N',
+                char_index: 0,
+                byte_index: 0
+            })
+        );
+        assert_eq!(
+            parser.parse("-NaN"),
+            Err(ParseError::UnexpectedCharacter {
+                character: 'N',
+                char_index: 1,
+                byte_index: 1,
+                expected_characters: ('0'..='9').collect(),
+                span: Span::single(0)
+            })
+        );
+        assert_eq!(
+            parser.parse("Infinit"),
+            Err(ParseError::UnexpectedEndOfString)
+        );
+    }
+
+    #[test]
+    fn malformed_comments() {
+        let parser = Parser::new().allow_comments(true);
+        assert_eq!(
+            parser.parse("[1 /x]"),
+            Err(ParseError::UnexpectedCharacter {
+                character: 'x',
+                char_index: 4,
+                byte_index: 4,
+                expected_characters: vec!['/', '*'],
+                span: Span::single(4)
+            })
+        );
+        assert_eq!(
+            parser.parse("[1 /* unterminated"),
+            Err(ParseError::UnexpectedEndOfString)
+        );
+        assert_eq!(
+            parser.parse("/* only a comment */"),
+            Err(ParseError::EmptyInput)
+        );
+        assert!(matches!(parser.parse("true // trailing"), Ok(v) if v == json!(true)));
+    }
+
+    #[test]
+    fn leading_plus_is_rejected() {
+        assert_eq!(
+            parse("+5"),
+            Err(ParseError::ExpectedValue {
+                found: '+',
+                char_index: 0,
+                byte_index: 0
+            })
+        );
+        let mut i = WhitespaceSkippingIndexTrackingIter::new("+5".chars());
+        let mut expected = vec!['-'];
+        expected.extend('0'..='9');
+        assert_eq!(
+            JsonNumber::parse(&mut i),
+            Err(ParseError::UnexpectedCharacter {
+                character: '+',
+                char_index: 0,
+                byte_index: 0,
+                expected_characters: expected,
+                span: Span::single(0)
+            })
+        );
+        assert!(matches!(parse("1e+5"), Ok(v) if v == json!(100000)));
+        assert!(matches!(parse("-1E-2"), Ok(v) if v == json!(-0.01)));
+    }
+
+    #[test]
+    fn error_span_covers_malformed_number() {
+        let error = parse("1..2").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::UnexpectedCharacter {
+                character: '.',
+                char_index: 2,
+                byte_index: 2,
+                expected_characters: ('0'..='9').collect(),
+                span: Span { start: 0, end: 4 }
+            }
+        );
+        assert_eq!(error.span(), Some(Span { start: 0, end: 4 }));
+        let error = parse("[-01, 2]").unwrap_err();
+        assert_eq!(error.span(), Some(Span { start: 1, end: 4 }));
+        let error = parse("[1e]").unwrap_err();
+        assert_eq!(error.span(), Some(Span { start: 1, end: 3 }));
+
+        let error = parse("[1 x]").unwrap_err();
+        assert_eq!(error.span(), Some(Span { start: 3, end: 4 }));
+        let error = parse("[] x").unwrap_err();
+        assert_eq!(error.span(), Some(Span { start: 3, end: 4 }));
+        assert_eq!(parse("[").unwrap_err().span(), None);
+    }
+
+    #[test]
+    fn expected_value_is_distinct_from_unexpected_character() {
+        assert_eq!(
+            parse("[1, ]"),
+            Err(ParseError::ExpectedValue {
+                found: ']',
+                char_index: 4,
+                byte_index: 4
+            })
+        );
+        assert_eq!(
+            parse(r#"{"a": }"#),
+            Err(ParseError::ExpectedValue {
+                found: '}',
+                char_index: 6,
+                byte_index: 6
+            })
+        );
+        assert_eq!(
+            parse("[1 2]"),
+            Err(ParseError::UnexpectedCharacter {
+                character: '2',
+                char_index: 3,
+                byte_index: 3,
+                expected_characters: vec![']', ','],
+                span: Span::single(3)
+            })
+        );
+        assert_eq!(parse("nul"), Err(ParseError::UnexpectedEndOfString));
+        assert_eq!(
+            parse("nulx"),
+            Err(ParseError::UnexpectedCharacter {
+                character: 'x',
+                char_index: 3,
+                byte_index: 3,
+                expected_characters: vec!['l'],
+                span: Span::single(3)
+            })
+        );
+    }
+
+    #[test]
+    fn expected_key_inside_objects() {
+        assert_eq!(
+            parse("{1:2}"),
+            Err(ParseError::ExpectedKey {
+                found: '1',
+                char_index: 1,
+                byte_index: 1,
+            })
+        );
+        assert_eq!(
+            parse(r#"{"a": 1, }"#),
+            Err(ParseError::ExpectedKey {
+                found: '}',
+                char_index: 9,
+                byte_index: 9,
+            })
+        );
+        assert_eq!(
+            parse("{'a': 1}"),
+            Err(ParseError::ExpectedKey {
+                found: '\'',
+                char_index: 1,
+                byte_index: 1
+            })
+        );
+        // Only the structure is checked up front, a malformed string key is
+        // still reported from inside it
+        assert_eq!(
+            parse(r#"{"a\x": 1}"#),
+            Err(ParseError::UnexpectedCharacter {
+                character: 'x',
+                char_index: 4,
+                byte_index: 4,
+                expected_characters: vec!['"', '\\', '/', 'b', 'f', 'n', 'r', 't', 'u'],
+                span: Span::single(4)
+            })
+        );
+    }
+
+    #[test]
+    fn errors_compare_and_clone() {
+        let error = parse("[1, 2 x]").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::UnexpectedCharacter {
+                character: 'x',
+                char_index: 6,
+                byte_index: 6,
+                expected_characters: vec![']', ','],
+                span: Span::single(6),
+            }
+        );
+        assert_eq!(error.clone(), error);
+        assert_ne!(error, ParseError::UnexpectedEndOfString);
+        assert_eq!(
+            parse("\"é\u{1}\"").unwrap_err(),
+            ParseError::ControlCharacter {
+                control_character: '\u{1}',
+                char_index: 2,
+                byte_index: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn errors_display_as_messages() {
+        let message = |input: &str| parse(input).unwrap_err().to_string();
+        assert_eq!(
+            message("[1, 2 x]"),
+            "unexpected 'x' at character 6, expected one of ']', ','"
+        );
+        assert_eq!(
+            message("[1, ]"),
+            "expected a value at character 4, found ']'"
+        );
+        assert_eq!(
+            message("[] x"),
+            "trailing 'x' at character 3 after the value"
+        );
+        assert_eq!(message(""), "empty input");
+        assert_eq!(
+            Parser::new().parse_array("{}").unwrap_err().to_string(),
+            "unexpected top-level object"
+        );
+        #[cfg(feature = "std")]
+        {
+            let error: Box<dyn std::error::Error> = Box::new(parse("[").unwrap_err());
+            assert_eq!(error.to_string(), "unexpected end of input");
+        }
+    }
+
+    #[test]
+    fn leading_zeros_are_rejected() {
+        assert_eq!(
+            parse("00"),
+            Err(ParseError::UnexpectedCharacter {
+                character: '0',
+                char_index: 1,
+                byte_index: 1,
+                expected_characters: vec!['.', 'e', 'E'],
+                span: Span { start: 0, end: 2 }
+            })
+        );
+        assert_eq!(
+            parse("-01"),
+            Err(ParseError::UnexpectedCharacter {
+                character: '1',
+                char_index: 2,
+                byte_index: 2,
+                expected_characters: vec!['.', 'e', 'E'],
+                span: Span { start: 0, end: 3 }
+            })
+        );
+        assert!(matches!(parse("0"), Ok(v) if v == json!(0)));
+        assert!(matches!(parse("0.5"), Ok(v) if v == json!(0.5)));
+        assert!(matches!(parse("0e1"), Ok(v) if v == json!(0)));
+    }
+
+    #[test]
+    fn debug_formatting() {
+        let value = json!({"a": [1, "x", null, true]});
+        assert_eq!(
+            format!("{value:?}"),
+            r#"Object({JsonString("a"): Array(JsonArray([Number(JsonNumber(1.0)), String(JsonString("x")), Null(JsonNull), Bool(JsonBool(true))]))})"#
+        );
+        let error = parse("[").unwrap_err();
+        assert_eq!(format!("{error:?}"), "UnexpectedEndOfString");
+    }
+
+    #[test]
+    fn reordered_objects_hash_equally() {
+        let mut set = std::collections::HashSet::new();
+        set.insert(json!({"a": 1, "b": [true, null], "c": {"x": 0, "y": "z"}}));
+        set.insert(json!({"c": {"y": "z", "x": -0.0}, "b": [true, null], "a": 1}));
+        assert_eq!(set.len(), 1);
+        set.insert(json!({"a": 1, "b": [null, true], "c": {"x": 0, "y": "z"}}));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn mutate_object() {
+        let Ok(JsonValue::Object(mut object)) = parse(r#"{"a": 1, "b": 2, "c": 3}"#) else {
+            panic!("expected an object");
+        };
+        let keys = |object: &JsonObject| -> Vec<String> {
+            object.iter().map(|(k, _)| k.0.clone()).collect()
+        };
+
+        assert_eq!(object.insert(JsonString("d".to_string()), json!(4)), None);
+        assert_eq!(keys(&object), ["a", "b", "c", "d"]);
+
+        assert_eq!(
+            object.insert(JsonString("b".to_string()), json!("two")),
+            Some(json!(2))
+        );
+        assert_eq!(keys(&object), ["a", "b", "c", "d"]);
+        assert_eq!(object.get("b"), Some(&json!("two")));
+
+        *object.get_mut("c").unwrap() = json!(null);
+        assert_eq!(object.get("c"), Some(&json!(null)));
+
+        assert_eq!(object.remove("a"), Some(json!(1)));
+        assert_eq!(object.remove("a"), None);
+        assert_eq!(keys(&object), ["b", "c", "d"]);
+
+        let entry = object.entry("e".to_string());
+        assert_eq!(entry.key(), "e");
+        *entry.or_insert(json!(0)) = json!(5);
+        assert_eq!(object.entry("d".to_string()).or_insert(json!(0)), &json!(4));
+        assert_eq!(keys(&object), ["b", "c", "d", "e"]);
+        assert_eq!(object.get("e"), Some(&json!(5)));
+    }
+
+    #[test]
+    fn get_or_insert_with() {
+        let mut object = JsonObject::default();
+        let tags = object.get_or_insert_with("tags", || json!([]));
+        assert_eq!(tags, &json!([]));
+        if let JsonValue::Array(tags) = tags {
+            tags.0.push(json!("a"));
+        }
+        let tags = object.get_or_insert_with("tags", || panic!("already present"));
+        assert_eq!(tags, &json!(["a"]));
+        *object.get_or_insert_with("count", || json!(0)) = json!(1);
+        let keys: Vec<&str> = object.keys().map(|k| k.0.as_str()).collect();
+        assert_eq!(keys, ["tags", "count"]);
+        assert_eq!(
+            JsonValue::Object(object),
+            json!({"tags": ["a"], "count": 1})
+        );
+    }
+
+    #[test]
+    fn parse_expected_top_level_kind() {
+        let object = parse_object(r#" {"a": [1]} "#).unwrap();
+        assert_eq!(object.get("a"), Some(&json!([1])));
+        assert_eq!(
+            parse_object("[1]"),
+            Err(ParseError::UnexpectedTopLevelKind {
+                found: ValueKind::Array
+            })
+        );
+        assert_eq!(
+            parse_object("null"),
+            Err(ParseError::UnexpectedTopLevelKind {
+                found: ValueKind::Null
+            })
+        );
+        assert_eq!(parse_object("{"), Err(ParseError::UnexpectedEndOfString));
+        // Rejected before the rest of the document is looked at
+        assert_eq!(
+            parse_object("[1, {"),
+            Err(ParseError::UnexpectedTopLevelKind {
+                found: ValueKind::Array
+            })
+        );
+        assert_eq!(
+            parse_object("x"),
+            Err(ParseError::ExpectedValue {
+                found: 'x',
+                char_index: 0,
+                byte_index: 0
+            })
+        );
+
+        let keyed = Parser::new().allow_non_string_keys(true);
+        let object = keyed.parse_object(r#"{"a": {1: 2}}"#).unwrap();
+        assert_eq!(object.get("a").unwrap().to_string(), "{1:2}");
+        assert_eq!(
+            keyed.parse_object("{1: 2}"),
+            Err(ParseError::ExpectedKey {
+                found: '1',
+                char_index: 1,
+                byte_index: 1
+            })
+        );
+
+        let array = parse_array("[true, {}]").unwrap();
+        assert_eq!(JsonValue::Array(array), json!([true, {}]));
+        assert_eq!(
+            parse_array(r#"{"a": 1}"#),
+            Err(ParseError::UnexpectedTopLevelKind {
+                found: ValueKind::Object
+            })
+        );
+        assert_eq!(
+            parse_array(r#""[]""#),
+            Err(ParseError::UnexpectedTopLevelKind {
+                found: ValueKind::String
+            })
+        );
+    }
+
+    #[test]
+    fn kind_and_type_name() {
+        let cases = [
+            (json!({}), ValueKind::Object, "object"),
+            (json!([]), ValueKind::Array, "array"),
+            (json!("s"), ValueKind::String, "string"),
+            (json!(1), ValueKind::Number, "number"),
+            (json!(false), ValueKind::Bool, "bool"),
+            (json!(null), ValueKind::Null, "null"),
+        ];
+        for (value, kind, name) in cases {
+            assert_eq!(value.kind(), kind);
+            assert_eq!(value.type_name(), name);
+        }
+    }
+
+    #[test]
+    fn max_string_length_limits_strings_and_keys() {
+        let parser = Parser::new().max_string_length(3);
+        assert_eq!(
+            parser.parse(r#"["abc", "\u00e9"]"#).unwrap(),
+            json!(["abc", "é"])
+        );
+        assert_eq!(
+            parser.parse(r#"["abcd"]"#),
+            Err(ParseError::StringTooLong {
+                char_index: 5,
+                byte_index: 5
+            })
+        );
+        assert_eq!(
+            parser.parse(r#"{"long key": 1}"#),
+            Err(ParseError::StringTooLong {
+                char_index: 5,
+                byte_index: 5
+            })
+        );
+        assert!(parse(r#"["abcd"]"#).is_ok());
+    }
+
+    #[test]
+    fn custom_whitespace() {
+        let input = "{\u{c}\"a\":\u{b}[1,\u{c}2]\u{c}}\u{c}";
+        assert_eq!(
+            parse(input),
+            Err(ParseError::ExpectedKey {
+                found: '\u{c}',
+                char_index: 1,
+                byte_index: 1
+            })
+        );
+        let parser = Parser::new().whitespace(&[' ', '\n', '\r', '\t', '\u{b}', '\u{c}']);
+        assert_eq!(parser.parse(input).unwrap(), json!({"a": [1, 2]}));
+        assert_eq!(
+            parser.parse("\"a\u{c}b\""),
+            Err(ParseError::ControlCharacter {
+                control_character: '\u{c}',
+                char_index: 2,
+                byte_index: 2
+            })
+        );
+        // Only the given characters count, even the spec's own are dropped
+        assert!(Parser::new()
+            .whitespace(&['\u{c}'])
+            .parse("[1, 2]")
+            .is_err());
+    }
+
+    #[test]
+    fn max_elements_limits_flat_arrays() {
+        let elements: Vec<String> = (0..10_000).map(|n| n.to_string()).collect();
+        let input = format!("[{}]", elements.join(","));
+        assert!(Parser::new().max_elements(10_001).parse(&input).is_ok());
+        // The array itself is the first of the ten, so element 9 is one too many
+        let nine = input.find(",9,").unwrap() + 1;
+        assert_eq!(
+            Parser::new().max_elements(10).parse(&input),
+            Err(ParseError::TooManyElements {
+                char_index: nine,
+                byte_index: nine
+            })
+        );
+        // Members count on top of their values
+        let parser = Parser::new().max_elements(3);
+        assert!(parser.parse(r#"{"a": 1}"#).is_ok());
+        assert_eq!(
+            parser.parse(r#"{"a": 1, "b": 2}"#),
+            Err(ParseError::TooManyElements {
+                char_index: 9,
+                byte_index: 9
+            })
+        );
+    }
+
+    #[test]
+    fn lenient_escapes_pass_through() {
+        let lenient = Parser::new().lenient_escapes(true);
+        assert_eq!(
+            lenient.parse(r#"["\x41", "C:\dir\n", "\é"]"#).unwrap(),
+            json!(["\\x41", "C:\\dir\n", "\\é"])
+        );
+        // Indistinguishable from an escaped quote, so the string never ends
+        assert_eq!(
+            lenient.parse(r#"["a\"]"#),
+            Err(ParseError::UnterminatedString {
+                char_index: 1,
+                byte_index: 1
+            })
+        );
+        assert_eq!(
+            lenient.parse(r#"["a\", "b"]"#),
+            Err(ParseError::UnexpectedCharacter {
+                character: 'b',
+                char_index: 8,
+                byte_index: 8,
+                expected_characters: vec![']', ','],
+                span: Span::single(8)
+            })
+        );
+        assert_eq!(
+            lenient.parse(r#""\u00x0""#),
+            Err(ParseError::UnexpectedNonHexCharacter {
+                character: 'x',
+                char_index: 5,
+                byte_index: 5
+            })
+        );
+        assert_eq!(
+            lenient.parse("\"\\\n\""),
+            Err(ParseError::UnexpectedCharacter {
+                character: '\n',
+                char_index: 2,
+                byte_index: 2,
+                expected_characters: vec!['"', '\\', '/', 'b', 'f', 'n', 'r', 't', 'u'],
+                span: Span::single(2)
+            })
+        );
+        assert_eq!(
+            parse(r#""\x41""#),
+            Err(ParseError::UnexpectedCharacter {
+                character: 'x',
+                char_index: 2,
+                byte_index: 2,
+                expected_characters: vec!['"', '\\', '/', 'b', 'f', 'n', 'r', 't', 'u'],
+                span: Span::single(2)
+            })
+        );
+    }
+
+    #[test]
+    fn surrogate_escapes() {
+        assert_eq!(parse(r#""😀 é""#).unwrap(), json!("\u{1F600} é"));
+        for (input, char_index) in [
+            (r#""\uD83Dx""#, 1),
+            (r#""\uDE00""#, 1),
+            (r#""ab\uD83D\n""#, 3),
+            (r#""\uD83D😀""#, 1),
+            (r#""é\uD83D""#, 2),
+        ] {
+            assert!(
+                matches!(
+                    parse(input),
+                    Err(ParseError::InvalidSurrogate { char_index: index, .. }) if index == char_index
+                ),
+                "{input}"
+            );
+        }
+        assert_eq!(
+            parse(r#""é\uD83D""#),
+            Err(ParseError::InvalidSurrogate {
+                char_index: 2,
+                byte_index: 3
+            })
+        );
+
+        let replacing = Parser::new().replace_invalid_surrogates(true);
+        assert_eq!(
+            replacing
+                .parse(r#"["\uD83Dx", "\uDE00", "ab\uD83D\n", "\uD83D😀", "\uD83D"]"#)
+                .unwrap(),
+            json!([
+                "\u{FFFD}x",
+                "\u{FFFD}",
+                "ab\u{FFFD}\n",
+                "\u{FFFD}\u{1F600}",
+                "\u{FFFD}"
+            ])
+        );
+    }
+
+    #[test]
+    fn reject_imprecise_numbers() {
+        let exact = Parser::new().reject_imprecise_numbers(true);
+        assert_eq!(
+            exact.parse("[1, 0.1]"),
+            Err(ParseError::ImpreciseNumber {
+                char_index: 4,
+                byte_index: 4
+            })
+        );
+        assert_eq!(parse("0.1").unwrap(), json!(0.1));
+        assert_eq!(
+            exact.parse("[0.5, -0.0, 0e5, 1.50e1, 0.0009765625, 1e22, 9007199254740992]"),
+            Ok(json!([
+                0.5,
+                0,
+                0,
+                15,
+                0.0009765625,
+                1e22,
+                9_007_199_254_740_992u64
+            ]))
+        );
+        // 2^53 + 1
+        assert_eq!(
+            exact.parse("9007199254740993"),
+            Err(ParseError::ImpreciseNumber {
+                char_index: 0,
+                byte_index: 0
+            })
+        );
+        for imprecise in ["1e23", "1e400", "1e-400", "5e-324", "0.30000000000000004"] {
+            assert!(
+                matches!(
+                    exact.parse(imprecise),
+                    Err(ParseError::ImpreciseNumber { .. })
+                ),
+                "{imprecise}"
+            );
+        }
+        assert!(exact.raw_numbers(true).parse("0.1").is_ok());
+    }
+
+    #[test]
+    fn max_exponent() {
+        let limited = Parser::new().max_exponent(308);
+        assert_eq!(
+            limited.parse("[1.5e308, 2E-300, 1e+0308]"),
+            Ok(json!([1.5e308, 2e-300, 1e308]))
+        );
+        assert_eq!(
+            limited.parse("[1, -1e1000000000]"),
+            Err(ParseError::ExponentTooLarge {
+                char_index: 4,
+                byte_index: 4
+            })
+        );
+        assert_eq!(
+            limited.parse("1e-99999999999999999999999"),
+            Err(ParseError::ExponentTooLarge {
+                char_index: 0,
+                byte_index: 0
+            })
+        );
+        assert_eq!(
+            limited.raw_numbers(true).parse("1e309"),
+            Err(ParseError::ExponentTooLarge {
+                char_index: 0,
+                byte_index: 0
+            })
+        );
+        assert_eq!(parse("1e1000000000"), Ok(json!(f64::INFINITY)));
+        assert_eq!(parse("-1e1000000000"), Ok(json!(f64::NEG_INFINITY)));
+    }
+
+    #[test]
+    fn unquoted_keys() {
+        let parser = Parser::new().allow_unquoted_keys(true);
+        assert_eq!(
+            parser
+                .parse(r#"{name: 1, value_2: "x", $id: null}"#)
+                .unwrap(),
+            json!({"name": 1, "value_2": "x", "$id": null})
+        );
+        assert_eq!(
+            parser
+                .parse(r#"{ _a : [1], "quoted key": 2, b: {c: true} }"#)
+                .unwrap(),
+            json!({"_a": [1], "quoted key": 2, "b": {"c": true}})
+        );
+        assert_eq!(
+            parser.parse("{1a: 2}"),
+            Err(ParseError::ExpectedKey {
+                found: '1',
+                char_index: 1,
+                byte_index: 1
+            })
+        );
+        assert_eq!(
+            parse("{name: 1}"),
+            Err(ParseError::ExpectedKey {
+                found: 'n',
+                char_index: 1,
+                byte_index: 1
+            })
+        );
+    }
+
+    #[test]
+    fn single_quoted_strings() {
+        let parser = Parser::new().allow_single_quotes(true);
+        assert_eq!(
+            parser
+                .parse(r#"{'key': 'say "hi"', "it's": 'it\'s'}"#)
+                .unwrap(),
+            json!({"key": "say \"hi\"", "it's": "it's"})
+        );
+        assert_eq!(
+            parser.parse("['abc\"]"),
+            Err(ParseError::UnterminatedString {
+                char_index: 1,
+                byte_index: 1
+            })
+        );
+        assert_eq!(
+            parse("'a'"),
+            Err(ParseError::ExpectedValue {
+                found: '\'',
+                char_index: 0,
+                byte_index: 0
+            })
+        );
+        assert_eq!(
+            parse(r#"["\'"]"#),
+            Err(ParseError::UnexpectedCharacter {
+                character: '\'',
+                char_index: 3,
+                byte_index: 3,
+                expected_characters: vec!['"', '\\', '/', 'b', 'f', 'n', 'r', 't', 'u'],
+                span: Span::single(3)
+            })
+        );
+    }
+
+    #[test]
+    fn large_integers() {
+        let raw = Parser::new().raw_numbers(true);
+        let number = |input: &str| match parse(input) {
+            Ok(JsonValue::Number(n)) => n,
+            other => panic!("{other:?}"),
+        };
+        let raw_number = |input: &str| match raw.parse(input) {
+            Ok(JsonValue::RawNumber(n)) => n,
+            other => panic!("{other:?}"),
+        };
+
+        assert_eq!(number("42").as_i64(), Some(42));
+        assert_eq!(number("-42").as_u64(), None);
+        assert_eq!(number("1.5").as_i64(), None);
+
+        // Just inside i64's range, but the nearest f64 is 2^63
+        let max = "9223372036854775807";
+        assert_eq!(number(max).0, 9_223_372_036_854_775_808.0);
+        assert_eq!(number(max).as_i64(), None);
+        assert_eq!(raw_number(max).as_i64(), Some(i64::MAX));
+        assert_eq!(raw.parse(max).unwrap().to_string(), max);
+
+        // Just beyond it
+        let beyond = "9223372036854775808";
+        assert_eq!(number(beyond).as_u64(), Some(1 << 63));
+        assert_eq!(raw_number(beyond).as_i64(), None);
+        assert_eq!(raw_number(beyond).as_u64(), Some(1 << 63));
+
+        // Beyond the range where f64 represents every integer
+        assert_eq!(number("9007199254740993").0, 9_007_199_254_740_992.0);
+        assert_eq!(
+            raw_number("9007199254740993").as_i64(),
+            Some(9_007_199_254_740_993)
+        );
+        let huge = "99999999999999999999";
+        assert_eq!(number(huge).0, 1e20);
+        assert_eq!(raw_number(huge).as_u64(), None);
+        assert_eq!(raw_number(huge).as_f64(), 1e20);
+        assert_eq!(raw_number(huge).as_str(), huge);
+
+        assert_eq!(
+            raw.parse("[1.50, -0, 2e3]").unwrap().to_string(),
+            "[1.50,-0,2e3]"
+        );
+        assert_eq!(
+            raw.parse("[1.50, -0]").unwrap().to_canonical_string(),
+            "[1.5,0]"
+        );
+
+        let preserved = Parser::new().preserve_number_text(true);
+        let value = preserved.parse("[1.50, -0, 2E3]").unwrap();
+        let JsonValue::Array(array) = &value else {
+            panic!("expected an array");
+        };
+        let JsonValue::Number(first) = &array.0[0] else {
+            panic!("expected a number");
+        };
+        assert_eq!(first.0, 1.5);
+        assert_eq!(first.source_text(), Some("1.50"));
+        assert_eq!(number("1.50").source_text(), None);
+        assert_eq!(value, json!([1.5, 0, 2000]));
+        assert_eq!(value.to_string(), "[1.50,-0,2E3]");
+        assert_eq!(value.to_canonical_string(), "[1.5,0,2000]");
+        let mut changed = first.clone();
+        changed.0 = 2.5;
+        assert_eq!(changed.source_text(), None);
+        assert_eq!(changed.to_string(), "2.5");
+        let JsonValue::Number(zero) = &array.0[1] else {
+            panic!("expected a number");
+        };
+        let mut zero = zero.clone();
+        zero.0 = 0.0;
+        assert_eq!(zero.to_string(), "0");
+        assert_eq!(
+            raw.parse("01"),
+            Err(ParseError::UnexpectedCharacter {
+                character: '1',
+                char_index: 1,
+                byte_index: 1,
+                expected_characters: vec!['.', 'e', 'E'],
+                span: Span { start: 0, end: 2 }
+            })
+        );
+    }
+
+    #[test]
+    fn parse_from_char_iterator() {
+        // Latin-1 bytes decoded on the fly, with the line breaks filtered out
+        let latin1 = b"{\"caf\xe9\":\r\n [1, true]}";
+        let chars = latin1
+            .iter()
+            .map(|&b| char::from(b))
+            .filter(|c| !matches!(c, '\r' | '\n'));
+        assert_eq!(parse_chars(chars).unwrap(), json!({"café": [1, true]}));
+
+        assert_eq!(
+            parse_chars("[é".chars().chain(" 2]".chars())),
+            Err(ParseError::ExpectedValue {
+                found: 'é',
+                char_index: 1,
+                byte_index: 1,
+            })
+        );
+        assert_eq!(
+            parse_chars("[1".chars().chain(" 2]".chars())),
+            Err(ParseError::UnexpectedCharacter {
+                character: '2',
+                char_index: 3,
+                byte_index: 3,
+                expected_characters: vec![']', ','],
+                span: Span::single(3),
+            })
+        );
+        assert_eq!(
+            Parser::new()
+                .allow_comments(true)
+                .parse_chars("é // c".chars().skip(1)),
+            Err(ParseError::EmptyInput)
+        );
+    }
+
+    #[test]
+    fn parse_concatenated_values_with_prefix() {
+        let input = "{}{}";
+        let (first, offset) = parse_prefix(input).unwrap();
+        assert_eq!((first, offset), (json!({}), 2));
+        let (second, rest) = parse_prefix(&input[offset..]).unwrap();
+        assert_eq!((second, rest), (json!({}), 2));
+
+        let mut input = "1 2\n \"é\"";
+        let mut values = Vec::new();
+        while !input.trim().is_empty() {
+            let (value, offset) = parse_prefix(input).unwrap();
+            values.push(value);
+            input = &input[offset..];
+        }
+        assert_eq!(values, [json!(1), json!(2), json!("é")]);
+
+        assert_eq!(parse_prefix("  [1] x"), Ok((json!([1]), 5)));
+        assert_eq!(parse_prefix(" "), Err(ParseError::EmptyInput));
+        assert_eq!(parse_prefix("[1"), Err(ParseError::UnexpectedEndOfString));
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_number_array() {
+        let document = format!(
+            "[{}]",
+            (0..200_000)
+                .map(|n| format!("{}.{}", n, n % 97))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let start = std::time::Instant::now();
+        for _ in 0..10 {
+            assert!(parse(&document).is_ok());
+        }
+        println!("{} bytes: {:?}", document.len(), start.elapsed() / 10);
+    }
+
+    #[test]
+    fn peeking_does_not_consume_or_move_indices() {
+        let input = "aé😀b";
+        let mut i = WhitespaceSkippingIndexTrackingIter::new(input.chars());
+        for expected in input.chars() {
+            for _ in 0..100 {
+                assert_eq!(i.peek(), Some(expected));
+            }
+            let char_index = i.next_char_index();
+            let byte_index = i.next_byte_index;
+            assert_eq!(i.next_if(|c| *c != expected), None);
+            assert_eq!(i.next_any(), Some(expected));
+            assert_eq!(i.previously_outputted_index, Some(char_index));
+            assert_eq!(i.previously_outputted_byte_index, Some(byte_index));
+        }
+        assert_eq!(i.peek(), None);
+        assert_eq!(i.next_any(), None);
+        assert_eq!(i.next_byte_index, input.len());
+    }
+
+    #[test]
+    fn errors_before_anything_is_consumed() {
+        let options = ParseOptions {
+            max_depth: Some(0),
+            ..ParseOptions::default()
+        };
+        let mut i = WhitespaceSkippingIndexTrackingIter::with_options("[".chars(), options);
+        assert_eq!(i.peek(), Some('['));
+        assert_eq!(
+            i.enter_nesting(),
+            Err(ParseError::MaxDepthExceeded {
+                char_index: 0,
+                byte_index: 0
+            })
+        );
+        assert_eq!(
+            i.unexpected_character('[', vec![']']),
+            ParseError::UnexpectedCharacter {
+                character: '[',
+                char_index: 0,
+                byte_index: 0,
+                expected_characters: vec![']'],
+                span: Span::single(0)
+            }
+        );
+        assert_eq!(
+            Parser::new().max_depth(0).parse("[]"),
+            Err(ParseError::MaxDepthExceeded {
+                char_index: 0,
+                byte_index: 0
+            })
+        );
+    }
+
+    #[test]
+    fn try_parse_never_panics() {
+        assert_eq!(try_parse(b""), Err(ParseError::EmptyInput));
+        assert_eq!(
+            try_parse(br#""\b\f\/""#),
+            Ok(JsonValue::from("\u{8}\u{c}/"))
+        );
+        assert_eq!(
+            try_parse(br#""\uD800""#),
+            Err(ParseError::InvalidSurrogate {
+                char_index: 1,
+                byte_index: 1
+            })
+        );
+        let deep = "[".repeat(1_000_000);
+        assert_eq!(
+            try_parse(deep.as_bytes()),
+            Err(ParseError::MaxDepthExceeded {
+                char_index: DEFAULT_MAX_DEPTH,
+                byte_index: DEFAULT_MAX_DEPTH
+            })
+        );
+    }
+
+    #[test]
+    fn fuzzed_inputs_never_panic() {
+        // Random bytes alone hardly ever get past the first character, so
+        // inputs are mostly glued together from pieces of JSON
+        const FRAGMENTS: &[&[u8]] = &[
+            b"{",
+            b"}",
+            b"[",
+            b"]",
+            b"\"",
+            b"'",
+            b",",
+            b":",
+            b" ",
+            b"\n",
+            b"\\",
+            b"\\u",
+            b"D83D",
+            b"DE00",
+            b"00e9",
+            b"b",
+            b"f",
+            b"/",
+            b"*",
+            b"true",
+            b"nul",
+            b"-",
+            b"0",
+            b"1.5e",
+            b"E+",
+            b"9",
+            b".",
+            b"key",
+            b"\xC3\xA9",
+            b"\xF0\x9F\x98\x80",
+            b"\xFF",
+            b"\xE2\x82",
+            b"\x01",
+            b"\x0C",
+            b"\xEF\xBB\xBF",
+        ];
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as usize
+        };
+        let parsers = [
+            Parser::new(),
+            Parser::new()
+                .allow_comments(true)
+                .allow_trailing_commas(true)
+                .allow_single_quotes(true)
+                .allow_unquoted_keys(true)
+                .lenient_escapes(true)
+                .replace_invalid_surrogates(true),
+            Parser::new()
+                .raw_numbers(true)
+                .allow_duplicate_keys(false)
+                .max_string_length(4)
+                .max_elements(8)
+                .max_exponent(2),
+            Parser::new()
+                .allow_non_string_keys(true)
+                .reject_imprecise_numbers(true)
+                .preserve_number_text(true)
+                .whitespace(&[' ', '\u{c}']),
+        ];
+        for _ in 0..5_000 {
+            let mut input = Vec::new();
+            for _ in 0..next() % 24 {
+                if next() % 4 == 0 {
+                    input.push(next() as u8);
+                } else {
+                    input.extend_from_slice(FRAGMENTS[next() % FRAGMENTS.len()]);
+                }
+            }
+            let text = String::from_utf8_lossy(&input);
+            let result = std::panic::catch_unwind(|| {
+                let _ = try_parse(&input);
+                for parser in &parsers {
+                    assert_eq!(parser.validate(&text), parser.parse(&text).map(drop));
+                    let _ = parser.parse_bytes(&input);
+                    let _ = parser.parse_borrowed(&text);
+                    let _ = parser.parse_interned(&text);
+                    let _ = parser.parse_recovering(&text);
+                }
+            });
+            assert!(result.is_ok(), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn char_indices_map_to_byte_offsets() {
+        let input = "[\"😀\", tru]";
+        let error = parse(input).unwrap_err();
+        let ParseError::UnexpectedCharacter {
+            char_index, span, ..
+        } = error
+        else {
+            panic!("unexpected error {error:?}");
+        };
+        assert_eq!(char_index, 9);
+        assert_eq!(char_to_byte_index(input, char_index), Some(12));
+        assert_eq!(&input[12..], "]");
+        assert_eq!(span.byte_range(input), Some(12..13));
+        assert_eq!(char_to_byte_index(input, 0), Some(0));
+        assert_eq!(char_to_byte_index(input, 10), Some(input.len()));
+        assert_eq!(char_to_byte_index(input, 11), None);
+    }
+
+    #[test]
+    fn position_after_parsing() {
+        let input = r#"["é", 2] rest"#;
+        let mut i = WhitespaceSkippingIndexTrackingIter::new(input.chars());
+        assert_eq!(
+            i.position(),
+            Position {
+                char_index: 0,
+                byte_index: 0
+            }
+        );
+        assert_eq!(JsonValue::parse(&mut i).unwrap(), json!(["é", 2]));
+        let position = i.position();
+        assert_eq!(
+            position,
+            Position {
+                char_index: 8,
+                byte_index: 9
+            }
+        );
+        assert_eq!(&input[position.byte_index..], " rest");
+        assert_eq!(parse_prefix(input).unwrap().1, position.byte_index);
+
+        // Numbers end on a peeked character, which isn't consumed
+        let mut i = WhitespaceSkippingIndexTrackingIter::new("12,".chars());
+        JsonValue::parse(&mut i).unwrap();
+        assert_eq!(i.position().char_index, 2);
+
+        let mut i = WhitespaceSkippingIndexTrackingIter::new("[1 x".chars());
+        assert!(JsonValue::parse(&mut i).is_err());
+        assert_eq!(i.position().char_index, 4);
+    }
+
+    #[test]
+    fn position_from_parser() {
+        let parser = Parser::new();
+        let input = r#" {"é": 1} [2]"#;
+        let (value, position) = parser.parse_prefix_with_position(input);
+        assert_eq!(value, Ok(json!({"é": 1})));
+        assert_eq!(
+            position,
+            Position {
+                char_index: 9,
+                byte_index: 10
+            }
+        );
+        assert_eq!(&input[position.byte_index..], " [2]");
+
+        let (value, position) = parser.parse_prefix_with_position(r#"["é", tru]"#);
+        assert_eq!(
+            value,
+            Err(ParseError::UnexpectedCharacter {
+                character: ']',
+                char_index: 9,
+                byte_index: 10,
+                expected_characters: vec!['e'],
+                span: Span::single(9)
+            })
+        );
+        assert_eq!(
+            position,
+            Position {
+                char_index: 10,
+                byte_index: 11
+            }
+        );
+
+        let (value, position) = parser.parse_prefix_with_position("  ");
+        assert_eq!(value, Err(ParseError::EmptyInput));
+        assert_eq!(position.char_index, 2);
+    }
+
+    #[test]
+    fn string_run_after_lookahead() {
+        let mut i = WhitespaceSkippingIndexTrackingIter::new("abc\"".chars());
+        assert_eq!(i.peek(), Some('a'));
+        assert_eq!(i.take_str_while(|c| c != '"'), None);
+        assert_eq!(i.next_any(), Some('a'));
+        assert_eq!(i.take_str_while(|c| c != '"'), Some("bc"));
+        assert_eq!(i.previously_outputted_index, Some(2));
+        assert_eq!(i.peek(), Some('"'));
+        assert_eq!(i.take_str_while(|c| c != '"'), Some(""));
+        assert_eq!(i.next_any(), Some('"'));
+    }
+
+    #[test]
+    fn scratch_buffer_limits_allocations() {
+        let escaped = r#""\tescapes\tforce\tchar\tby\tchar\tpushes\t""#;
+        let document = format!(
+            "[{}, {}]",
+            vec![escaped; 1000].join(","),
+            vec!["12345.678e-3"; 1000].join(",")
+        );
+        let (value, allocations) = count_allocations(|| parse(&document));
+        let Ok(JsonValue::Array(array)) = value else {
+            panic!("expected an array");
+        };
+        assert_eq!(array.len(), 2000);
+        assert_eq!(
+            array.0[0],
+            json!("\tescapes\tforce\tchar\tby\tchar\tpushes\t")
+        );
+        assert_eq!(array.0[1999], json!(12.345678));
+        // One allocation per string, plus growing the array and the scratch
+        // buffer, with nothing for the numbers
+        assert!(allocations < 1050, "{allocations} allocations");
+    }
+}
@@ -0,0 +1,154 @@
+/// Builds a [`JsonValue`](crate::JsonValue) from JSON-like syntax.
+///
+/// Object keys are string literals or a parenthesized expression producing a
+/// `&str`/`String`. Any other value position accepts a Rust expression that
+/// converts into a `JsonValue` via `From`.
+///
+/// ```
+/// use json_parser::json;
+///
+/// let name = "x";
+/// let value = json!({"name": name, "nums": [1, 2, 3], "ok": true, "nil": null});
+/// assert_eq!(value.to_string(), r#"{"name":"x","nums":[1,2,3],"ok":true,"nil":null}"#);
+/// ```
+#[macro_export]
+macro_rules! json {
+    // Internal rules come first, since a failed `expr` match in the public
+    // rules below is a hard error rather than a fallthrough.
+    //
+    // Array elements are munched one at a time. `null`, arrays and objects
+    // have to be matched before `expr` so they get recursed into instead of
+    // being captured as opaque expressions.
+    (@array [$($elements:expr,)*]) => {
+        $crate::__private::vec![$($elements,)*]
+    };
+    (@array [$($elements:expr,)*] , $($rest:tt)*) => {
+        $crate::json!(@array [$($elements,)*] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] null $($rest:tt)*) => {
+        $crate::json!(@array [$($elements,)* $crate::json!(null),] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::json!(@array [$($elements,)* $crate::json!([$($array)*]),] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] {$($object:tt)*} $($rest:tt)*) => {
+        $crate::json!(@array [$($elements,)* $crate::json!({$($object)*}),] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] $next:expr , $($rest:tt)*) => {
+        $crate::json!(@array [$($elements,)* $crate::json!($next),] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] $last:expr) => {
+        $crate::json!(@array [$($elements,)* $crate::json!($last),])
+    };
+
+    // Object members are munched the same way, inserting as they go
+    (@object $object:ident ()) => {};
+    (@object $object:ident (, $($rest:tt)*)) => {
+        $crate::json!(@object $object ($($rest)*))
+    };
+    (@object $object:ident ($key:tt : null $($rest:tt)*)) => {
+        $crate::json!(@insert $object $key $crate::json!(null));
+        $crate::json!(@object $object ($($rest)*))
+    };
+    (@object $object:ident ($key:tt : [$($array:tt)*] $($rest:tt)*)) => {
+        $crate::json!(@insert $object $key $crate::json!([$($array)*]));
+        $crate::json!(@object $object ($($rest)*))
+    };
+    (@object $object:ident ($key:tt : {$($inner:tt)*} $($rest:tt)*)) => {
+        $crate::json!(@insert $object $key $crate::json!({$($inner)*}));
+        $crate::json!(@object $object ($($rest)*))
+    };
+    (@object $object:ident ($key:tt : $value:expr , $($rest:tt)*)) => {
+        $crate::json!(@insert $object $key $crate::json!($value));
+        $crate::json!(@object $object ($($rest)*))
+    };
+    (@object $object:ident ($key:tt : $value:expr)) => {
+        $crate::json!(@insert $object $key $crate::json!($value));
+    };
+    (@insert $object:ident $key:tt $value:expr) => {
+        $object.insert(
+            $crate::JsonString($crate::__private::String::from($key)),
+            $value,
+        );
+    };
+
+    (null) => {
+        $crate::JsonValue::Null($crate::JsonNull)
+    };
+    ([]) => {
+        $crate::JsonValue::Array($crate::JsonArray::default())
+    };
+    ([ $($tt:tt)+ ]) => {
+        $crate::JsonValue::Array($crate::JsonArray($crate::json!(@array [] $($tt)+)))
+    };
+    ({}) => {
+        $crate::JsonValue::Object($crate::JsonObject::default())
+    };
+    ({ $($tt:tt)+ }) => {
+        $crate::JsonValue::Object({
+            let mut object = $crate::JsonObject::default();
+            $crate::json!(@object object ($($tt)+));
+            object
+        })
+    };
+    ($other:expr) => {
+        $crate::JsonValue::from($other)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{JsonArray, JsonBool, JsonNull, JsonNumber, JsonObject, JsonString, JsonValue};
+
+    #[test]
+    fn builds_nested_value() {
+        let name = String::from("x");
+        let extra = 4;
+        let built = json!({
+            "name": name,
+            "nums": [1, 2.5, -3, extra],
+            "ok": true,
+            "nil": null,
+            "nested": {"empty": [], "obj": {}, ("computed".to_string()): [null, [false]]},
+        });
+
+        let string = |s: &str| JsonString(s.to_string());
+        let number = |n: f64| JsonValue::Number(JsonNumber(n, None));
+        let nested = vec![
+            (string("empty"), JsonValue::Array(JsonArray(vec![]))),
+            (string("obj"), JsonValue::Object(JsonObject(vec![]))),
+            (
+                string("computed"),
+                JsonValue::Array(JsonArray(vec![
+                    JsonValue::Null(JsonNull),
+                    JsonValue::Array(JsonArray(vec![JsonValue::Bool(JsonBool(false))])),
+                ])),
+            ),
+        ];
+        let expected = vec![
+            (string("name"), JsonValue::String(string("x"))),
+            (
+                string("nums"),
+                JsonValue::Array(JsonArray(vec![
+                    number(1.0),
+                    number(2.5),
+                    number(-3.0),
+                    number(4.0),
+                ])),
+            ),
+            (string("ok"), JsonValue::Bool(JsonBool(true))),
+            (string("nil"), JsonValue::Null(JsonNull)),
+            (string("nested"), JsonValue::Object(JsonObject(nested))),
+        ];
+
+        assert!(built == JsonValue::Object(JsonObject(expected)));
+    }
+
+    #[test]
+    fn matches_parsed_document() {
+        let Ok(parsed) = crate::parse(r#"{"a": [1, "two", null], "b": {"c": false}}"#) else {
+            panic!("failed to parse");
+        };
+        assert!(parsed == json!({"a": [1, "two", null], "b": {"c": false}}));
+    }
+}
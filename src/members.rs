@@ -0,0 +1,172 @@
+use crate::DuplicateKeyPolicy;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash};
+
+/// A key and value as stored in the members of one of the value trees, so
+/// they can share [`insert_member`] and [`KeyIndex`]
+pub(crate) trait Member: Sized {
+    type Key: ?Sized + Eq + Hash;
+
+    fn key(&self) -> &Self::Key;
+
+    /// The members of the value, if it's an object a repeated key can be
+    /// merged into
+    fn object_members(&mut self) -> Option<&mut Vec<Self>>;
+}
+
+/// Adds a parsed member, handling a repeated key according to `policy`. A
+/// replaced member takes the place of the earlier one, a merged object keeps
+/// it and has the new members inserted in turn.
+/// [`DuplicateKeyPolicy::Error`] has to be checked before the value is
+/// parsed, here it's the same as `LastWins`.
+///
+/// `index` has to be used for `members` only, and kept across calls.
+pub(crate) fn insert_member<M: Member>(
+    members: &mut Vec<M>,
+    index: &mut KeyIndex,
+    mut member: M,
+    policy: DuplicateKeyPolicy,
+) {
+    let Some(position) = index.position(members, member.key()) else {
+        members.push(member);
+        return;
+    };
+    if policy == DuplicateKeyPolicy::Merge {
+        if let (Some(existing), Some(new)) =
+            (members[position].object_members(), member.object_members())
+        {
+            let mut nested_index = KeyIndex::default();
+            for nested in core::mem::take(new) {
+                insert_member(existing, &mut nested_index, nested, policy);
+            }
+            return;
+        }
+    }
+    members[position] = member;
+}
+
+/// Objects up to this many members are searched linearly, which is cheaper
+/// than hashing for the small objects most documents are made of
+const INDEX_THRESHOLD: usize = 16;
+
+/// Finds members by key in a hashed lookup rather than a scan, so building
+/// or comparing an object with `n` members doesn't take `n²` key
+/// comparisons. Nothing is hashed until the object outgrows
+/// [`INDEX_THRESHOLD`], after which members appended since the last lookup
+/// are indexed as needed. Members may be appended or replaced by one with
+/// the same key in between lookups, but not removed or reordered.
+#[derive(Default)]
+pub(crate) struct KeyIndex {
+    /// The hash of each key to the position of the first member with it.
+    /// Keys that collide with an earlier one aren't in here, they're found
+    /// by falling back to a scan.
+    positions: BTreeMap<u64, usize>,
+    /// How many of the members have been indexed
+    indexed: usize,
+    hasher: KeyHasher,
+}
+
+/// Seeded per index with `std`, so crafted keys can't be made to collide.
+/// Without it there's no source of randomness, and the fixed
+/// [`Fnv1a`](crate::Fnv1a) leaves objects with deliberately colliding keys
+/// as slow as a scan.
+#[cfg(feature = "std")]
+type KeyHasher = std::hash::RandomState;
+#[cfg(not(feature = "std"))]
+type KeyHasher = core::hash::BuildHasherDefault<crate::Fnv1a>;
+
+impl KeyIndex {
+    /// Position of the member with `key` in `members`
+    pub(crate) fn position<M: Member>(&mut self, members: &[M], key: &M::Key) -> Option<usize> {
+        if members.len() <= INDEX_THRESHOLD {
+            return members.iter().position(|m| m.key() == key);
+        }
+        for (position, member) in members.iter().enumerate().skip(self.indexed) {
+            let hash = self.hasher.hash_one(member.key());
+            self.positions.entry(hash).or_insert(position);
+        }
+        self.indexed = members.len();
+        let position = *self.positions.get(&self.hasher.hash_one(key))?;
+        if members[position].key() == key {
+            return Some(position);
+        }
+        members.iter().position(|m| m.key() == key)
+    }
+
+    /// The member with `key` in `members`
+    pub(crate) fn get<'m, M: Member>(&mut self, members: &'m [M], key: &M::Key) -> Option<&'m M> {
+        self.position(members, key)
+            .map(|position| &members[position])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, JsonValue, ParseError, Parser};
+
+    fn object_text(keys: impl Iterator<Item = usize>) -> String {
+        let members: Vec<String> = keys.map(|k| format!(r#""k{k}": {k}"#)).collect();
+        format!("{{{}}}", members.join(", "))
+    }
+
+    #[test]
+    fn large_objects_find_repeated_keys() {
+        let text = object_text((0..1000).chain([3, 500, 999]));
+        let value = parse(&text).unwrap();
+        assert_eq!(value.len(), Some(1000));
+        assert_eq!(value, parse(&object_text((0..1000).rev())).unwrap());
+        assert_ne!(value, parse(&object_text(1..1001)).unwrap());
+
+        let error = Parser::new().allow_duplicate_keys(false).parse(&text);
+        let repeat = text.rfind(r#""k3""#).unwrap();
+        assert_eq!(
+            error,
+            Err(ParseError::DuplicateKey {
+                key: "k3".to_string(),
+                char_index: repeat,
+                byte_index: repeat
+            })
+        );
+
+        let merged = Parser::new()
+            .duplicate_key_policy(DuplicateKeyPolicy::Merge)
+            .parse(&format!(
+                r#"{{"a": {}, "a": {}}}"#,
+                object_text(0..100),
+                object_text(50..150)
+            ))
+            .unwrap();
+        let JsonValue::Object(merged) = merged else {
+            panic!("expected an object");
+        };
+        assert_eq!(merged.get("a").and_then(JsonValue::len), Some(150));
+    }
+
+    /// Keys without values
+    impl Member for (String, ()) {
+        type Key = str;
+
+        fn key(&self) -> &str {
+            &self.0
+        }
+
+        fn object_members(&mut self) -> Option<&mut Vec<Self>> {
+            None
+        }
+    }
+
+    #[test]
+    fn colliding_hashes_fall_back_to_a_scan() {
+        let members: Vec<_> = (0..100).map(|k| (format!("k{k}"), ())).collect();
+        let mut index = KeyIndex::default();
+        assert_eq!(index.position(&members, "k40"), Some(40));
+        // Point every hash at the wrong member
+        for position in index.positions.values_mut() {
+            *position = 0;
+        }
+        assert_eq!(index.position(&members, "k70"), Some(70));
+        assert_eq!(index.position(&members, "k100"), None);
+    }
+}
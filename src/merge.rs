@@ -0,0 +1,122 @@
+use crate::keyed::JsonKeyedObject;
+use crate::members::{KeyIndex, Member};
+use crate::{JsonArray, JsonObject, JsonValue};
+use alloc::vec::Vec;
+
+/// Combines two documents into a new one, `b` winning where they conflict.
+///
+/// Objects are merged member by member: keys only in one side are copied
+/// over, and a key in both has its two values merged in turn. Members keep
+/// the order of `a`, with the keys only `b` has appended in its order.
+/// Arrays are concatenated, `a`'s elements followed by `b`'s, rather than
+/// `b` replacing `a` the way RFC 7386 merge patch does, so lists collected
+/// from several sources add up. Anything else, including an object on one
+/// side and an array on the other, is replaced by `b`, and `null` in `b` is
+/// just another value rather than a deletion.
+pub fn deep_merge(a: &JsonValue, b: &JsonValue) -> JsonValue {
+    match (a, b) {
+        (JsonValue::Object(a), JsonValue::Object(b)) => {
+            JsonValue::Object(JsonObject(merge_members(&a.0, &b.0)))
+        }
+        (JsonValue::KeyedObject(a), JsonValue::KeyedObject(b)) => {
+            JsonValue::KeyedObject(JsonKeyedObject(merge_members(&a.0, &b.0)))
+        }
+        (JsonValue::Array(a), JsonValue::Array(b)) => {
+            JsonValue::Array(JsonArray(a.0.iter().chain(&b.0).cloned().collect()))
+        }
+        _ => b.clone(),
+    }
+}
+
+/// The members of two objects of the same kind, merged as [`deep_merge`]
+/// describes
+fn merge_members<K: Clone>(a: &[(K, JsonValue)], b: &[(K, JsonValue)]) -> Vec<(K, JsonValue)>
+where
+    (K, JsonValue): Member<Key = K>,
+{
+    let mut merged = Vec::with_capacity(a.len() + b.len());
+    let (mut a_index, mut b_index) = (KeyIndex::default(), KeyIndex::default());
+    for (key, a_value) in a {
+        let value = match b_index.get(b, key) {
+            Some((_, b_value)) => deep_merge(a_value, b_value),
+            None => a_value.clone(),
+        };
+        merged.push((key.clone(), value));
+    }
+    for (key, b_value) in b {
+        if a_index.position(a, key).is_none() {
+            merged.push((key.clone(), b_value.clone()));
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_nested_objects() {
+        let a = json!({"name": "a", "db": {"host": "localhost", "port": 1}, "only_a": true});
+        let b = json!({"db": {"port": 2, "user": "b"}, "name": "b", "only_b": null});
+        let merged = deep_merge(&a, &b);
+        assert_eq!(
+            merged,
+            json!({
+                "name": "b",
+                "db": {"host": "localhost", "port": 2, "user": "b"},
+                "only_a": true,
+                "only_b": null
+            })
+        );
+        let JsonValue::Object(object) = &merged else {
+            panic!("expected an object");
+        };
+        let keys: Vec<&str> = object.keys().map(|k| k.0.as_str()).collect();
+        assert_eq!(keys, ["name", "db", "only_a", "only_b"]);
+        assert_eq!(
+            a,
+            json!({"name": "a", "db": {"host": "localhost", "port": 1}, "only_a": true})
+        );
+        assert_eq!(
+            b,
+            json!({"db": {"port": 2, "user": "b"}, "name": "b", "only_b": null})
+        );
+    }
+
+    #[test]
+    fn scalars_and_mismatches_take_the_right_side() {
+        assert_eq!(deep_merge(&json!(1), &json!("x")), json!("x"));
+        assert_eq!(deep_merge(&json!({"a": 1}), &json!(null)), json!(null));
+        assert_eq!(
+            deep_merge(&json!({"a": [1]}), &json!({"a": {"b": 2}})),
+            json!({"a": {"b": 2}})
+        );
+        assert_eq!(deep_merge(&json!([1]), &json!({})), json!({}));
+    }
+
+    #[test]
+    fn concatenates_arrays() {
+        assert_eq!(
+            deep_merge(
+                &json!({"tags": ["a", "b"]}),
+                &json!({"tags": ["b", {"c": 1}]})
+            ),
+            json!({"tags": ["a", "b", "b", {"c": 1}]})
+        );
+        assert_eq!(deep_merge(&json!([]), &json!([1])), json!([1]));
+    }
+
+    #[test]
+    fn merges_keyed_objects() {
+        let parser = crate::Parser::new().allow_non_string_keys(true);
+        let a = parser.parse(r#"{1: {"x": 1}, true: [1]}"#).unwrap();
+        let b = parser
+            .parse(r#"{null: 0, 1: {"y": 2}, true: [2]}"#)
+            .unwrap();
+        assert_eq!(
+            deep_merge(&a, &b).to_string(),
+            r#"{1:{"x":1,"y":2},true:[1,2],null:0}"#
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use crate::JsonValue;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Why a JSON Pointer (RFC 6901) couldn't be followed. Paths are the part of
+/// the pointer that was followed, re-escaped.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PointerError {
+    /// Not empty and not starting with `/`, or a `~` not followed by `0` or
+    /// `1`
+    Syntax,
+    /// Nothing is at `path`. Array elements must be named by an index without
+    /// leading zeros.
+    NotFound { path: String },
+    /// The value at `path` is a scalar, so there's nothing below it
+    NotAContainer { path: String },
+}
+
+impl core::fmt::Display for PointerError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PointerError::Syntax => f.write_str("invalid JSON Pointer"),
+            PointerError::NotFound { path } => write!(f, "nothing at {path:?}"),
+            PointerError::NotAContainer { path } => {
+                write!(f, "{path:?} is neither an array nor an object")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PointerError {}
+
+/// Splits `pointer` into its unescaped reference tokens, none for `""`
+fn tokens(pointer: &str) -> Result<Vec<String>, PointerError> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let rest = pointer.strip_prefix('/').ok_or(PointerError::Syntax)?;
+    rest.split('/')
+        .map(|token| {
+            let mut unescaped = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(c) = chars.next() {
+                if c != '~' {
+                    unescaped.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('0') => unescaped.push('~'),
+                    Some('1') => unescaped.push('/'),
+                    _ => return Err(PointerError::Syntax),
+                }
+            }
+            Ok(unescaped)
+        })
+        .collect()
+}
+
+/// Re-escapes `tokens` into a pointer
+fn path_of(tokens: &[String]) -> String {
+    tokens
+        .iter()
+        .fold(String::new(), |path, token| push_token(&path, token))
+}
+
+/// `0` or a number without leading zeros
+fn array_index(token: &str) -> Option<usize> {
+    let valid = token == "0" || (!token.starts_with('0') && !token.is_empty());
+    if valid && token.bytes().all(|b| b.is_ascii_digit()) {
+        token.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Follows `tokens[n]` from `value`, which was reached through `tokens[..n]`
+fn child_mut<'a>(
+    value: &'a mut JsonValue,
+    tokens: &[String],
+    n: usize,
+) -> Result<&'a mut JsonValue, PointerError> {
+    let token = &tokens[n];
+    let child = match value {
+        JsonValue::Object(object) => object.get_mut(token),
+        JsonValue::KeyedObject(object) => object
+            .0
+            .iter_mut()
+            .find(|(key, _)| key.text() == token.as_str())
+            .map(|(_, value)| value),
+        JsonValue::Array(array) => array_index(token).and_then(|index| array.0.get_mut(index)),
+        _ => {
+            return Err(PointerError::NotAContainer {
+                path: path_of(&tokens[..n]),
+            })
+        }
+    };
+    child.ok_or_else(|| PointerError::NotFound {
+        path: path_of(&tokens[..=n]),
+    })
+}
+
+/// Appends `token` to the JSON Pointer `path` as a new reference token,
+/// escaping `~` and `/`
+pub(crate) fn push_token(path: &str, token: &str) -> String {
+    let mut child = String::with_capacity(path.len() + token.len() + 1);
+    child.push_str(path);
+    child.push('/');
+    for c in token.chars() {
+        match c {
+            '~' => child.push_str("~0"),
+            '/' => child.push_str("~1"),
+            c => child.push(c),
+        }
+    }
+    child
+}
+
+impl JsonValue {
+    /// Replaces the value `pointer` names with `value`. Only the value must
+    /// already exist, nothing on the way is created. The exception is a last
+    /// token of `-` on an array, which appends `value` to it. The empty
+    /// pointer replaces the whole document.
+    pub fn set_pointer(&mut self, pointer: &str, value: JsonValue) -> Result<(), PointerError> {
+        let tokens = tokens(pointer)?;
+        let Some(last) = tokens.len().checked_sub(1) else {
+            *self = value;
+            return Ok(());
+        };
+        let mut target = self;
+        for n in 0..last {
+            target = child_mut(target, &tokens, n)?;
+        }
+        match target {
+            JsonValue::Array(array) if tokens[last] == "-" => array.0.push(value),
+            target => *child_mut(target, &tokens, last)? = value,
+        }
+        Ok(())
+    }
+
+    /// Every leaf with its JSON Pointer path, in depth-first order. Leaves
+    /// are scalars and empty arrays and objects, so no part of the document
+    /// is lost. A scalar document is a single leaf at `""`. Keys of a keyed
+    /// object that aren't strings are named by their JSON text, see
+    /// [`JsonKey::text`](crate::keyed::JsonKey::text).
+    pub fn flatten(&self) -> Vec<(String, &JsonValue)> {
+        fn collect<'a>(
+            path: String,
+            value: &'a JsonValue,
+            leaves: &mut Vec<(String, &'a JsonValue)>,
+        ) {
+            match value {
+                JsonValue::Object(object) if !object.is_empty() => {
+                    for (key, value) in object.iter() {
+                        collect(push_token(&path, &key.0), value, leaves);
+                    }
+                }
+                JsonValue::KeyedObject(object) if !object.is_empty() => {
+                    for (key, value) in object.iter() {
+                        collect(push_token(&path, &key.text()), value, leaves);
+                    }
+                }
+                JsonValue::Array(array) if !array.is_empty() => {
+                    for (index, value) in array.iter().enumerate() {
+                        collect(push_token(&path, &index.to_string()), value, leaves);
+                    }
+                }
+                _ => leaves.push((path, value)),
+            }
+        }
+        let mut leaves = Vec::new();
+        collect(String::new(), self, &mut leaves);
+        leaves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_pointer_replaces_and_appends() {
+        let mut value = json!({"a": {"b": [1, {"c": "old"}]}, "x/y": {"~": 0}});
+        value.set_pointer("/a/b/1/c", json!("new")).unwrap();
+        value.set_pointer("/a/b/-", json!(3)).unwrap();
+        value.set_pointer("/a/b/0", json!(null)).unwrap();
+        value.set_pointer("/x~1y/~0", json!(true)).unwrap();
+        assert_eq!(
+            value,
+            json!({"a": {"b": [null, {"c": "new"}, 3]}, "x/y": {"~": true}})
+        );
+        value.set_pointer("", json!([])).unwrap();
+        assert_eq!(value, json!([]));
+    }
+
+    #[test]
+    fn set_pointer_creates_nothing() {
+        let mut value = json!({"a": {"b": [1]}, "s": "text"});
+        assert_eq!(
+            value.set_pointer("/missing/b", json!(1)),
+            Err(PointerError::NotFound {
+                path: "/missing".to_string()
+            })
+        );
+        assert_eq!(
+            value.set_pointer("/a/c", json!(1)),
+            Err(PointerError::NotFound {
+                path: "/a/c".to_string()
+            })
+        );
+        for index in ["1", "01", "-1", "x"] {
+            assert_eq!(
+                value.set_pointer(&format!("/a/b/{index}"), json!(1)),
+                Err(PointerError::NotFound {
+                    path: format!("/a/b/{index}")
+                })
+            );
+        }
+        assert_eq!(
+            value.set_pointer("/a/-", json!(1)),
+            Err(PointerError::NotFound {
+                path: "/a/-".to_string()
+            })
+        );
+        assert_eq!(
+            value.set_pointer("/s/0", json!(1)),
+            Err(PointerError::NotAContainer {
+                path: "/s".to_string()
+            })
+        );
+        assert_eq!(value.set_pointer("a", json!(1)), Err(PointerError::Syntax));
+        assert_eq!(
+            value.set_pointer("/a~2", json!(1)),
+            Err(PointerError::Syntax)
+        );
+        assert_eq!(value, json!({"a": {"b": [1]}, "s": "text"}));
+        let message = |pointer: &str| value.clone().set_pointer(pointer, json!(1)).unwrap_err();
+        assert_eq!(message("/a/c").to_string(), r#"nothing at "/a/c""#);
+        assert_eq!(
+            message("/s/0").to_string(),
+            r#""/s" is neither an array nor an object"#
+        );
+        assert_eq!(message("a").to_string(), "invalid JSON Pointer");
+    }
+
+    #[test]
+    fn flatten_nested_document() {
+        let value = json!({
+            "a": [{"b": 1}, "c"],
+            "d/e": {"f~g": null},
+            "h": [],
+            "i": {}
+        });
+        let flattened: Vec<_> = value
+            .flatten()
+            .into_iter()
+            .map(|(path, value)| (path, value.to_string()))
+            .collect();
+        let expected = [
+            ("/a/0/b", "1"),
+            ("/a/1", r#""c""#),
+            ("/d~1e/f~0g", "null"),
+            ("/h", "[]"),
+            ("/i", "{}"),
+        ]
+        .map(|(path, value)| (path.to_string(), value.to_string()));
+        assert_eq!(flattened, expected);
+        assert_eq!(json!(true).flatten(), [(String::new(), &json!(true))]);
+
+        let mut keyed = crate::Parser::new()
+            .allow_non_string_keys(true)
+            .parse(r#"{"a": {1: [true]}, null: {}}"#)
+            .unwrap();
+        let paths: Vec<_> = keyed.flatten().into_iter().map(|(path, _)| path).collect();
+        assert_eq!(paths, ["/a/1/0", "/null"]);
+        keyed.set_pointer("/a/1/0", json!(false)).unwrap();
+        assert_eq!(keyed.to_string(), r#"{"a":{1:[false]},null:{}}"#);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::JsonValue;
+
+/// What [`JsonValue::remove_nulls_with`] removes besides object members
+/// that are `null`. The default removes nothing else.
+#[derive(Clone, Debug, Default)]
+pub struct PruneOptions {
+    /// Also remove `null` array elements, shifting the later ones down.
+    /// Arrays are often positional, so they're kept by default.
+    pub remove_array_nulls: bool,
+    /// Also remove objects and arrays that pruning left empty, which can in
+    /// turn empty their parent. Ones that were empty to begin with are kept,
+    /// as is the top-level value.
+    pub remove_emptied_containers: bool,
+}
+
+/// Prunes what's inside `value`, returning whether that emptied it
+fn prune(value: &mut JsonValue, options: &PruneOptions) -> bool {
+    match value {
+        JsonValue::Object(object) => {
+            let was_empty = object.is_empty();
+            object
+                .0
+                .retain_mut(|(_, v)| !should_remove(v, options, true));
+            !was_empty && object.is_empty()
+        }
+        JsonValue::KeyedObject(object) => {
+            let was_empty = object.is_empty();
+            object
+                .0
+                .retain_mut(|(_, v)| !should_remove(v, options, true));
+            !was_empty && object.is_empty()
+        }
+        JsonValue::Array(array) => {
+            let was_empty = array.is_empty();
+            array.0.retain_mut(|v| !should_remove(v, options, false));
+            !was_empty && array.is_empty()
+        }
+        _ => false,
+    }
+}
+
+/// Prunes `value`, then decides whether it goes as well
+fn should_remove(value: &mut JsonValue, options: &PruneOptions, in_object: bool) -> bool {
+    let emptied = prune(value, options);
+    match value {
+        JsonValue::Null(_) => in_object || options.remove_array_nulls,
+        _ => emptied && options.remove_emptied_containers,
+    }
+}
+
+impl JsonValue {
+    /// Recursively removes every object member whose value is `null`. Array
+    /// elements are left alone, see [`JsonValue::remove_nulls_with`].
+    pub fn remove_nulls(&mut self) {
+        self.remove_nulls_with(&PruneOptions::default());
+    }
+
+    /// [`JsonValue::remove_nulls`], removing more according to `options`.
+    /// A top-level `null` is left as it is.
+    pub fn remove_nulls_with(&mut self, options: &PruneOptions) {
+        prune(self, options);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> JsonValue {
+        json!({
+            "a": null,
+            "b": {"c": null, "d": [1, null, {"e": null}], "f": {}},
+            "g": [null, [null]],
+            "h": 0
+        })
+    }
+
+    #[test]
+    fn removes_null_members_at_every_depth() {
+        let mut value = document();
+        value.remove_nulls();
+        assert_eq!(
+            value,
+            json!({"b": {"d": [1, null, {}], "f": {}}, "g": [null, [null]], "h": 0})
+        );
+    }
+
+    #[test]
+    fn options_remove_array_nulls_and_emptied_containers() {
+        let mut value = document();
+        value.remove_nulls_with(&PruneOptions {
+            remove_array_nulls: true,
+            ..PruneOptions::default()
+        });
+        assert_eq!(
+            value,
+            json!({"b": {"d": [1, {}], "f": {}}, "g": [[]], "h": 0})
+        );
+
+        let mut value = document();
+        value.remove_nulls_with(&PruneOptions {
+            remove_array_nulls: true,
+            remove_emptied_containers: true,
+        });
+        assert_eq!(value, json!({"b": {"d": [1], "f": {}}, "h": 0}));
+
+        let mut value = json!({"a": null});
+        value.remove_nulls_with(&PruneOptions {
+            remove_array_nulls: true,
+            remove_emptied_containers: true,
+        });
+        assert_eq!(value, json!({}));
+    }
+}
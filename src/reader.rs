@@ -0,0 +1,324 @@
+use crate::{
+    parse_document_in, CharIterator, JsonType, JsonValue, ParseError, Parser,
+    WhitespaceSkippingIndexTrackingIter,
+};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// How many bytes [`ReadChars`] asks its reader for at a time
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Reduces `e` to a [`ParseError::Io`]
+fn io_error(e: io::Error) -> ParseError {
+    ParseError::Io {
+        kind: e.kind(),
+        message: e.to_string(),
+    }
+}
+
+/// A [`CharIterator`] decoding UTF-8 from an [`io::Read`], refilling a fixed
+/// buffer as it goes, so characters can straddle two reads.
+///
+/// `CharIterator` has no way to fail, so an I/O error or invalid UTF-8 ends
+/// the characters early and is kept in [`ReadChars::error`] for the caller
+/// to report instead of whatever the parser made of the early end.
+struct ReadChars<R: Read> {
+    reader: R,
+    buffer: Box<[u8]>,
+    /// Next unconsumed byte in `buffer`
+    start: usize,
+    /// End of the bytes the last read filled `buffer` with
+    end: usize,
+    /// Characters and bytes consumed so far, for [`ParseError::InvalidUtf8`]
+    char_index: usize,
+    byte_index: usize,
+    error: Option<ParseError>,
+}
+
+impl<R: Read> ReadChars<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: vec![0; READ_BUFFER_SIZE].into_boxed_slice(),
+            start: 0,
+            end: 0,
+            char_index: 0,
+            byte_index: 0,
+            error: None,
+        }
+    }
+
+    /// The next byte without consuming it, reading more if the buffer is
+    /// used up. `None` at the end of the input or on an I/O error.
+    fn peek_byte(&mut self) -> Option<u8> {
+        while self.start == self.end {
+            if self.error.is_some() {
+                return None;
+            }
+            match self.reader.read(&mut self.buffer) {
+                Ok(0) => return None,
+                Ok(read) => {
+                    self.start = 0;
+                    self.end = read;
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => self.error = Some(io_error(e)),
+            }
+        }
+        Some(self.buffer[self.start])
+    }
+
+    /// Records that the sequence starting at the current position isn't
+    /// UTF-8, unless an I/O error got there first
+    fn invalid_utf8(&mut self) -> Option<char> {
+        self.error.get_or_insert(ParseError::InvalidUtf8 {
+            char_index: self.char_index,
+            byte_index: self.byte_index,
+        });
+        None
+    }
+}
+
+impl<R: Read> CharIterator for ReadChars<R> {
+    fn next(&mut self) -> Option<char> {
+        let first = self.peek_byte()?;
+        let len = match first {
+            0x00..=0x7F => 1,
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => return self.invalid_utf8(),
+        };
+        // The indices only move once the whole sequence is known to be valid,
+        // so an error points at its first byte
+        let mut bytes = [first, 0, 0, 0];
+        let mut consumed = 1;
+        self.start += 1;
+        while consumed < len {
+            match self.peek_byte() {
+                Some(byte) if byte & 0xC0 == 0x80 => {
+                    bytes[consumed] = byte;
+                    consumed += 1;
+                    self.start += 1;
+                }
+                _ => return self.invalid_utf8(),
+            }
+        }
+        let c = std::str::from_utf8(&bytes[..len])
+            .ok()
+            .and_then(|s| Iterator::next(&mut s.chars()));
+        match c {
+            Some(c) => {
+                self.char_index += 1;
+                self.byte_index += len;
+                Some(c)
+            }
+            None => self.invalid_utf8(),
+        }
+    }
+}
+
+/// Yields the values of a concatenated JSON stream, see
+/// [`Parser::parse_stream`]
+struct ValueStream<R: Read> {
+    i: WhitespaceSkippingIndexTrackingIter<ReadChars<R>>,
+    /// Set once the input is over or an error was yielded
+    done: bool,
+}
+
+impl<R: Read> ValueStream<R> {
+    /// Parses the next value, `None` once only whitespace is left
+    fn advance(&mut self) -> Result<Option<JsonValue>, ParseError> {
+        let i = &mut self.i;
+        if i.peek_non_whitespace()?.is_none() {
+            return Ok(None);
+        }
+        // Each value is a document of its own as far as limits go
+        i.elements = 0;
+        JsonValue::parse(i).map(Some)
+    }
+}
+
+impl<R: Read> Iterator for ValueStream<R> {
+    type Item = Result<JsonValue, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut result = self.advance();
+        // The parser only saw the characters stop early
+        if let Some(error) = self.i.inner.error.take() {
+            result = Err(error);
+        }
+        let result = result.transpose();
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+        result
+    }
+}
+
+impl Parser {
+    /// Parses a stream of JSON values read from `reader` one after another,
+    /// separated by nothing but optional whitespace, e.g. `{}[]true 42`. Each
+    /// value is yielded as soon as it's complete. Errors, including I/O errors
+    /// as [`ParseError::Io`], are yielded as the last item, and their indices
+    /// count from the start of the stream. A leading byte order mark is
+    /// skipped, and a stream with no values yields nothing.
+    pub fn parse_stream<R: Read>(
+        &self,
+        reader: R,
+    ) -> impl Iterator<Item = Result<JsonValue, ParseError>> {
+        let mut i = WhitespaceSkippingIndexTrackingIter::with_options(
+            ReadChars::new(reader),
+            self.options.clone(),
+        );
+        i.next_if(|c| *c == '\u{FEFF}');
+        ValueStream { i, done: false }
+    }
+
+    /// Parses the file at `path` as a single document, like [`Parser::parse`]
+    /// but reading it in chunks rather than loading it whole. Failing to open
+    /// or read the file is a [`ParseError::Io`].
+    pub fn parse_file<P: AsRef<Path>>(&self, path: P) -> Result<JsonValue, ParseError> {
+        let file = File::open(path).map_err(io_error)?;
+        let mut i = WhitespaceSkippingIndexTrackingIter::with_options(
+            ReadChars::new(file),
+            self.options.clone(),
+        );
+        let result = parse_document_in(&mut i, JsonValue::parse);
+        // Takes precedence over whatever the parser made of the early end
+        match i.inner.error.take() {
+            Some(error) => Err(error),
+            None => result,
+        }
+    }
+}
+
+/// [`Parser::parse_stream`] with the default [`Parser`]
+pub fn parse_stream<R: Read>(reader: R) -> impl Iterator<Item = Result<JsonValue, ParseError>> {
+    Parser::new().parse_stream(reader)
+}
+
+/// [`Parser::parse_file`] with the default [`Parser`]
+pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<JsonValue, ParseError> {
+    Parser::new().parse_file(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hands out at most one byte per read, then fails if told to
+    struct TrickleReader<'a> {
+        bytes: &'a [u8],
+        fail_at_end: bool,
+    }
+
+    impl Read for TrickleReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.bytes.split_first() {
+                Some((&byte, rest)) => {
+                    buf[0] = byte;
+                    self.bytes = rest;
+                    Ok(1)
+                }
+                None if self.fail_at_end => Err(io::Error::other("connection reset")),
+                None => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn yields_concatenated_values() {
+        let values: Vec<_> = parse_stream("{}[]true 42".as_bytes()).collect();
+        assert_eq!(
+            values,
+            [Ok(json!({})), Ok(json!([])), Ok(json!(true)), Ok(json!(42))]
+        );
+        assert_eq!(parse_stream(" \n ".as_bytes()).count(), 0);
+    }
+
+    #[test]
+    fn values_straddle_reads() {
+        let input = "\u{FEFF}{\"é😀\": [1.5, null]}\n\"ü\" 7";
+        let reader = TrickleReader {
+            bytes: input.as_bytes(),
+            fail_at_end: false,
+        };
+        let values: Vec<_> = parse_stream(reader).collect();
+        assert_eq!(
+            values,
+            [
+                Ok(json!({"é😀": [1.5, null]})),
+                Ok(json!("ü")),
+                Ok(json!(7))
+            ]
+        );
+    }
+
+    #[test]
+    fn errors_end_the_stream() {
+        let values: Vec<_> = parse_stream("1 [2,] 3".as_bytes()).collect();
+        assert_eq!(
+            values,
+            [
+                Ok(json!(1)),
+                Err(ParseError::ExpectedValue {
+                    found: ']',
+                    char_index: 5,
+                    byte_index: 5
+                })
+            ]
+        );
+
+        let values: Vec<_> = parse_stream(&b"[\"\xC3\xA9\xFF\"]"[..]).collect();
+        assert_eq!(
+            values,
+            [Err(ParseError::InvalidUtf8 {
+                char_index: 3,
+                byte_index: 4
+            })]
+        );
+
+        let reader = TrickleReader {
+            bytes: b"[1] [2, ",
+            fail_at_end: true,
+        };
+        let values: Vec<_> = parse_stream(reader).collect();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0], Ok(json!([1])));
+        assert!(matches!(
+            &values[1],
+            Err(ParseError::Io { kind: io::ErrorKind::Other, message }) if message == "connection reset"
+        ));
+    }
+
+    #[test]
+    fn parses_files() {
+        let path = std::env::temp_dir().join(format!("json_parser_{}.json", std::process::id()));
+        std::fs::write(&path, "\u{FEFF}{\"é\": [1, true]}\n").unwrap();
+        let parsed = parse_file(&path);
+        std::fs::write(&path, "[1] 2").unwrap();
+        let trailing = parse_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(parsed, Ok(json!({"é": [1, true]})));
+        assert_eq!(
+            trailing,
+            Err(ParseError::TrailingCharacter {
+                character: '2',
+                char_index: 4,
+                byte_index: 4
+            })
+        );
+        assert!(matches!(
+            parse_file(&path),
+            Err(ParseError::Io {
+                kind: io::ErrorKind::NotFound,
+                ..
+            })
+        ));
+    }
+}
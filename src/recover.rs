@@ -0,0 +1,369 @@
+use crate::members::{insert_member, KeyIndex};
+use crate::{
+    CharIterator, DuplicateKeyPolicy, JsonArray, JsonObject, JsonString, JsonType, JsonValue,
+    ParseError, Parser, WhitespaceSkippingIndexTrackingIter,
+};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Delimiters that parsing resumes at after an error
+const RESYNC_CHARS: [char; 3] = [',', ']', '}'];
+
+/// The outcome of [`Parser::parse_recovering`]
+#[derive(Debug)]
+pub struct Recovered {
+    /// Everything that could be parsed, `None` if not even the start of the
+    /// top-level value could be
+    pub value: Option<JsonValue>,
+    /// In the order they were encountered, empty for a valid document
+    pub errors: Vec<ParseError>,
+}
+
+impl Parser {
+    /// Parses like [`Parser::parse`], but keeps going after an error and
+    /// returns every error along with a best-effort value, for editors and
+    /// linters.
+    ///
+    /// After an error, parsing skips ahead to the next `,`, `]` or `}` and
+    /// carries on as if that delimiter had directly followed the last good
+    /// element or member. The element or member that failed is left out. If
+    /// the offending character was itself one of those delimiters, parsing
+    /// carries on from it instead of skipping. The skip doesn't look inside
+    /// strings or track nesting, so a delimiter inside a broken string or a
+    /// mismatched bracket can cause follow-up errors. A container nested
+    /// deeper than [`ParseOptions::max_depth`](crate::ParseOptions::max_depth)
+    /// is skipped as a whole and kept as an empty one.
+    ///
+    /// Keys are always strings,
+    /// [`ParseOptions::allow_non_string_keys`](crate::ParseOptions::allow_non_string_keys)
+    /// isn't supported.
+    pub fn parse_recovering(&self, input: &str) -> Recovered {
+        let mut i =
+            WhitespaceSkippingIndexTrackingIter::with_options(input.chars(), self.options.clone());
+        let mut errors = Vec::new();
+        i.next_if(|c| *c == '\u{FEFF}');
+        match i.peek_non_whitespace() {
+            Ok(Some(_)) => {}
+            Ok(None) => errors.push(ParseError::EmptyInput),
+            Err(e) => errors.push(e),
+        }
+        if !errors.is_empty() {
+            return Recovered {
+                value: None,
+                errors,
+            };
+        }
+        let value = parse_value(&mut i, &mut errors).ok();
+        if value.is_some() {
+            match i.next_non_whitespace() {
+                Ok(Some(c)) => errors.push(ParseError::TrailingCharacter {
+                    character: c,
+                    char_index: i.previous_char_index(),
+                    byte_index: i.previous_byte_index(),
+                }),
+                Ok(None) => {}
+                Err(e) => errors.push(e),
+            }
+        }
+        Recovered { value, errors }
+    }
+}
+
+/// Consumes everything up to the next resync delimiter, leaving it unconsumed
+fn skip_to_delimiter<CI: CharIterator>(i: &mut WhitespaceSkippingIndexTrackingIter<CI>) {
+    while i.next_if(|c| !RESYNC_CHARS.contains(c)).is_some() {}
+}
+
+/// Records `error` and skips to the next delimiter. Returns the delimiter if
+/// the error was about one, since it has already been consumed.
+fn resync<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    errors: &mut Vec<ParseError>,
+    error: ParseError,
+) -> Option<char> {
+    let consumed = match &error {
+        ParseError::UnexpectedCharacter { character, .. }
+        | ParseError::ExpectedValue {
+            found: character, ..
+        }
+        | ParseError::ExpectedKey {
+            found: character, ..
+        } if RESYNC_CHARS.contains(character) => Some(*character),
+        _ => None,
+    };
+    errors.push(error);
+    if consumed.is_none() {
+        skip_to_delimiter(i);
+    }
+    consumed
+}
+
+/// Containers always succeed with whatever could be parsed, so only a failed
+/// scalar is an `Err`, holding the delimiter [`resync`] already consumed
+fn parse_value<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    errors: &mut Vec<ParseError>,
+) -> Result<JsonValue, Option<char>> {
+    let next_char = match i.peek_non_whitespace() {
+        Ok(Some(c)) => c,
+        Ok(None) => return Err(resync(i, errors, ParseError::UnexpectedEndOfString)),
+        Err(e) => return Err(resync(i, errors, e)),
+    };
+    // Scalars are counted by `JsonValue::parse`
+    if matches!(next_char, '{' | '[') {
+        i.count_element().map_err(|e| resync(i, errors, e))?;
+    }
+    match next_char {
+        '{' => Ok(JsonValue::Object(parse_object(i, errors))),
+        '[' => Ok(JsonValue::Array(parse_array(i, errors))),
+        _ => JsonValue::parse(i).map_err(|e| resync(i, errors, e)),
+    }
+}
+
+/// Consumes the opening bracket and checks the nesting limit, then returns
+/// whether the container is immediately closed. A container past the limit
+/// is skipped and treated as empty, since descending into it is what the
+/// limit guards against.
+fn open_container<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    errors: &mut Vec<ParseError>,
+    close: char,
+) -> bool {
+    i.next_any();
+    if let Err(e) = i.enter_nesting() {
+        errors.push(e);
+        skip_container(i);
+        return true;
+    }
+    match i.next_non_whitespace_if_eq(close) {
+        Ok(closed) => closed.is_some(),
+        Err(e) => {
+            errors.push(e);
+            false
+        }
+    }
+}
+
+/// Consumes the rest of a container whose opening bracket was just consumed,
+/// up to its closing bracket or the end of input. Brackets inside strings
+/// are ignored, but any closing bracket matches any opening one.
+fn skip_container<CI: CharIterator>(i: &mut WhitespaceSkippingIndexTrackingIter<CI>) {
+    let mut depth = 1;
+    let mut quote = None;
+    while let Some(c) = i.next_any() {
+        match (quote, c) {
+            (Some(_), '\\') => {
+                i.next_any();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"') => quote = Some('"'),
+            (None, '\'') if i.options.allow_single_quotes => quote = Some('\''),
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return;
+                }
+            }
+            (None, _) => {}
+        }
+    }
+}
+
+/// Handles what follows an element or member, starting from `pending` if a
+/// delimiter was already consumed. Returns `true` once the container is
+/// closed or the input runs out, `false` if another element or member follows.
+fn close_or_continue<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    errors: &mut Vec<ParseError>,
+    close: char,
+    mut pending: Option<char>,
+) -> bool {
+    loop {
+        let c = match pending.take() {
+            Some(c) => c,
+            None => match i.next_non_whitespace() {
+                Ok(Some(c)) => c,
+                Ok(None) => {
+                    // Reported once, rather than once per open container
+                    if !matches!(errors.last(), Some(ParseError::UnexpectedEndOfString)) {
+                        errors.push(ParseError::UnexpectedEndOfString);
+                    }
+                    return true;
+                }
+                Err(e) => {
+                    pending = resync(i, errors, e);
+                    continue;
+                }
+            },
+        };
+        if c == close {
+            return true;
+        }
+        if c == ',' {
+            if i.options.allow_trailing_commas {
+                match i.next_non_whitespace_if_eq(close) {
+                    Ok(Some(_)) => return true,
+                    Ok(None) => {}
+                    Err(e) => {
+                        pending = resync(i, errors, e);
+                        continue;
+                    }
+                }
+            }
+            return false;
+        }
+        // The delimiter was consumed and reported here, so it mustn't become
+        // pending again
+        errors.push(i.unexpected_character(c, vec![close, ',']));
+        skip_to_delimiter(i);
+    }
+}
+
+fn parse_array<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    errors: &mut Vec<ParseError>,
+) -> JsonArray {
+    let mut elements = Vec::new();
+    let mut closed = open_container(i, errors, ']');
+    while !closed {
+        let pending = match parse_value(i, errors) {
+            Ok(value) => {
+                elements.push(value);
+                None
+            }
+            Err(pending) => pending,
+        };
+        closed = close_or_continue(i, errors, ']', pending);
+    }
+    i.exit_nesting();
+    JsonArray(elements)
+}
+
+fn parse_object<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    errors: &mut Vec<ParseError>,
+) -> JsonObject {
+    let mut object = JsonObject(Vec::new());
+    let mut index = KeyIndex::default();
+    let mut closed = open_container(i, errors, '}');
+    while !closed {
+        let pending = match parse_member(i, errors, &object, &mut index) {
+            Ok((key, value)) => {
+                let policy = i.options.duplicate_key_policy;
+                insert_member(&mut object.0, &mut index, (key, value), policy);
+                None
+            }
+            Err(pending) => pending,
+        };
+        closed = close_or_continue(i, errors, '}', pending);
+    }
+    i.exit_nesting();
+    object
+}
+
+/// A disallowed duplicate key is reported, but the member is still kept
+fn parse_member<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    errors: &mut Vec<ParseError>,
+    object: &JsonObject,
+    index: &mut KeyIndex,
+) -> Result<(JsonString, JsonValue), Option<char>> {
+    i.skip_whitespace().map_err(|e| resync(i, errors, e))?;
+    i.count_element().map_err(|e| resync(i, errors, e))?;
+    let key_char_index = i.next_char_index();
+    let key_byte_index = i.next_byte_index;
+    let key = JsonString::parse_key(i).map_err(|e| resync(i, errors, e))?;
+    if i.options.duplicate_key_policy == DuplicateKeyPolicy::Error
+        && index.position(&object.0, &key).is_some()
+    {
+        errors.push(ParseError::DuplicateKey {
+            key: key.0.clone(),
+            char_index: key_char_index,
+            byte_index: key_byte_index,
+        });
+    }
+    i.expect_specific_char_ignore_whitespace(':')
+        .map_err(|e| resync(i, errors, e))?;
+    let value = parse_value(i, errors)?;
+    Ok((key, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Span;
+
+    #[test]
+    fn reports_every_error() {
+        let input = r#"{"a": tru, "b": [1, 2 3], "c": 4}"#;
+        let recovered = Parser::new().parse_recovering(input);
+        assert_eq!(
+            recovered.errors,
+            [
+                ParseError::UnexpectedCharacter {
+                    character: ',',
+                    char_index: 9,
+                    byte_index: 9,
+                    expected_characters: vec!['e'],
+                    span: Span::single(9)
+                },
+                ParseError::UnexpectedCharacter {
+                    character: '3',
+                    char_index: 22,
+                    byte_index: 22,
+                    expected_characters: vec![']', ','],
+                    span: Span::single(22)
+                }
+            ]
+        );
+        let expected = crate::parse(r#"{"b": [1, 2], "c": 4}"#).unwrap();
+        assert_eq!(recovered.value, Some(expected));
+    }
+
+    #[test]
+    fn valid_and_truncated_documents() {
+        let input = r#"{"a": [1, {"b": null}], "c": "d"}"#;
+        let recovered = Parser::new().parse_recovering(input);
+        assert!(recovered.errors.is_empty());
+        assert_eq!(recovered.value, Some(crate::parse(input).unwrap()));
+
+        let recovered = Parser::new().parse_recovering(r#"[1, [2, "#);
+        assert_eq!(recovered.errors, [ParseError::UnexpectedEndOfString]);
+        assert_eq!(recovered.value, Some(json!([1, [2]])));
+
+        let recovered = Parser::new().parse_recovering(" ");
+        assert!(recovered.value.is_none());
+        assert_eq!(recovered.errors, [ParseError::EmptyInput]);
+    }
+
+    #[test]
+    fn too_deep_containers_are_skipped() {
+        let parser = Parser::new().max_depth(2);
+        let recovered = parser.parse_recovering(r#"[[["]", {"a": [}]], 1], 2]"#);
+        assert_eq!(
+            recovered.errors,
+            [ParseError::MaxDepthExceeded {
+                char_index: 2,
+                byte_index: 2
+            }]
+        );
+        assert_eq!(recovered.value, Some(json!([[[], 1], 2])));
+
+        // Would overflow the stack if the skipped containers were descended into
+        let recovered = Parser::new()
+            .max_depth(10)
+            .parse_recovering(&"[".repeat(200_000));
+        assert_eq!(
+            recovered.errors,
+            [
+                ParseError::MaxDepthExceeded {
+                    char_index: 10,
+                    byte_index: 10
+                },
+                ParseError::UnexpectedEndOfString
+            ]
+        );
+    }
+}
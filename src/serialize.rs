@@ -0,0 +1,698 @@
+use crate::keyed::{JsonKey, JsonKeyedObject};
+use crate::sorted::JsonObjectSorted;
+use crate::{
+    JsonArray, JsonBool, JsonNull, JsonNumber, JsonObject, JsonRawNumber, JsonString, JsonValue,
+};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+const PRETTY_INDENT: &str = "  ";
+
+/// Output settings that don't change the JSON data model, the default matches
+/// `Display`
+#[derive(Clone, Debug, Default)]
+pub struct SerializeOptions {
+    /// Escape every non-ASCII character as `\uXXXX`, using surrogate pairs
+    /// outside the Basic Multilingual Plane
+    pub ascii_only: bool,
+    /// Write every `/` in strings as `\/`, so the output can't close an
+    /// HTML `<script>` element it's embedded in
+    pub escape_forward_slash: bool,
+}
+
+/// Writes `s` as a quoted JSON string. Unescaped runs are written in bulk,
+/// anything the parser would reject as a raw control character is escaped.
+fn write_escaped_str<W: fmt::Write>(w: &mut W, s: &str, options: &SerializeOptions) -> fmt::Result {
+    w.write_char('"')?;
+    let mut run_start = 0;
+    for (index, c) in s.char_indices() {
+        let escaped = match c {
+            '"' => "\\\"",
+            '\\' => "\\\\",
+            '\n' => "\\n",
+            '\r' => "\\r",
+            '\t' => "\\t",
+            '\u{8}' => "\\b",
+            '\u{c}' => "\\f",
+            '/' if options.escape_forward_slash => "\\/",
+            c if c.is_control() || (options.ascii_only && !c.is_ascii()) => "",
+            _ => continue,
+        };
+        w.write_str(&s[run_start..index])?;
+        if escaped.is_empty() {
+            for unit in c.encode_utf16(&mut [0; 2]) {
+                write!(w, "\\u{:04x}", unit)?;
+            }
+        } else {
+            w.write_str(escaped)?;
+        }
+        run_start = index + c.len_utf8();
+    }
+    w.write_str(&s[run_start..])?;
+    w.write_char('"')
+}
+
+/// Writes the shortest decimal that parses back to the exact same `f64`.
+/// Magnitudes outside `1e-7..1e21` use exponent notation to avoid long runs of
+/// zeros. JSON can't represent NaN or infinity, so those are written as `null`.
+fn write_number<W: fmt::Write>(w: &mut W, n: f64) -> fmt::Result {
+    if !n.is_finite() {
+        return w.write_str("null");
+    }
+    let magnitude = n.abs();
+    if magnitude != 0.0 && !(1e-7..1e21).contains(&magnitude) {
+        write!(w, "{n:e}")
+    } else {
+        write!(w, "{n}")
+    }
+}
+
+/// `depth` is `None` for compact output, otherwise the current nesting level
+fn write_newline_and_indent<W: fmt::Write>(w: &mut W, depth: Option<usize>) -> fmt::Result {
+    if let Some(depth) = depth {
+        w.write_char('\n')?;
+        for _ in 0..depth {
+            w.write_str(PRETTY_INDENT)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_value<W: fmt::Write>(
+    w: &mut W,
+    value: &JsonValue,
+    depth: Option<usize>,
+    options: &SerializeOptions,
+) -> fmt::Result {
+    match value {
+        JsonValue::Object(object) => write_object(w, object, depth, options),
+        JsonValue::KeyedObject(object) => write_members(w, object.iter(), depth, options),
+        JsonValue::Array(array) => write_array(w, array, depth, options),
+        JsonValue::String(string) => write_escaped_str(w, &string.0, options),
+        JsonValue::Number(number) => match number.source_text() {
+            Some(text) => w.write_str(text),
+            None => write_number(w, number.0),
+        },
+        JsonValue::RawNumber(number) => w.write_str(number.as_str()),
+        JsonValue::Bool(b) => w.write_str(if b.0 { "true" } else { "false" }),
+        JsonValue::Null(_) => w.write_str("null"),
+    }
+}
+
+fn write_array<W: fmt::Write>(
+    w: &mut W,
+    array: &JsonArray,
+    depth: Option<usize>,
+    options: &SerializeOptions,
+) -> fmt::Result {
+    if array.0.is_empty() {
+        return w.write_str("[]");
+    }
+    let inner_depth = depth.map(|d| d + 1);
+    w.write_char('[')?;
+    for (index, value) in array.0.iter().enumerate() {
+        if index != 0 {
+            w.write_char(',')?;
+        }
+        write_newline_and_indent(w, inner_depth)?;
+        write_value(w, value, inner_depth, options)?;
+    }
+    write_newline_and_indent(w, depth)?;
+    w.write_char(']')
+}
+
+fn write_object<W: fmt::Write>(
+    w: &mut W,
+    object: &JsonObject,
+    depth: Option<usize>,
+    options: &SerializeOptions,
+) -> fmt::Result {
+    write_members(w, object.iter(), depth, options)
+}
+
+/// How [`write_members`] writes a key
+trait ObjectKey {
+    fn write_key<W: fmt::Write>(&self, w: &mut W, options: &SerializeOptions) -> fmt::Result;
+}
+
+impl ObjectKey for JsonString {
+    fn write_key<W: fmt::Write>(&self, w: &mut W, options: &SerializeOptions) -> fmt::Result {
+        write_escaped_str(w, &self.0, options)
+    }
+}
+
+/// Keys that aren't strings are written unquoted, see [`JsonKeyedObject`]
+impl ObjectKey for JsonKey {
+    fn write_key<W: fmt::Write>(&self, w: &mut W, options: &SerializeOptions) -> fmt::Result {
+        write_value(w, &self.0, None, options)
+    }
+}
+
+fn write_members<'a, K: ObjectKey + 'a, W: fmt::Write>(
+    w: &mut W,
+    members: impl ExactSizeIterator<Item = (&'a K, &'a JsonValue)>,
+    depth: Option<usize>,
+    options: &SerializeOptions,
+) -> fmt::Result {
+    if members.len() == 0 {
+        return w.write_str("{}");
+    }
+    let inner_depth = depth.map(|d| d + 1);
+    w.write_char('{')?;
+    for (index, (key, value)) in members.enumerate() {
+        if index != 0 {
+            w.write_char(',')?;
+        }
+        write_newline_and_indent(w, inner_depth)?;
+        key.write_key(w, options)?;
+        w.write_str(if depth.is_some() { ": " } else { ":" })?;
+        write_value(w, value, inner_depth, options)?;
+    }
+    write_newline_and_indent(w, depth)?;
+    w.write_char('}')
+}
+
+/// Compact output with object members sorted by key, see
+/// [`JsonValue::to_canonical_string`]
+fn write_canonical<W: fmt::Write>(
+    w: &mut W,
+    value: &JsonValue,
+    options: &SerializeOptions,
+) -> fmt::Result {
+    match value {
+        JsonValue::Object(object) => {
+            let mut members: Vec<_> = object.iter().collect();
+            // Byte order of UTF-8 is the same as code point order
+            members.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0));
+            w.write_char('{')?;
+            for (index, (key, value)) in members.into_iter().enumerate() {
+                if index != 0 {
+                    w.write_char(',')?;
+                }
+                write_escaped_str(w, &key.0, options)?;
+                w.write_char(':')?;
+                write_canonical(w, value, options)?;
+            }
+            w.write_char('}')
+        }
+        // Sorted by the canonical text of the keys, which puts string keys,
+        // starting with `"`, before numbers
+        JsonValue::KeyedObject(object) => {
+            let mut members: Vec<_> = object
+                .iter()
+                .map(|(key, value)| (key.0.to_canonical_string(), key, value))
+                .collect();
+            members.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+            w.write_char('{')?;
+            for (index, (_, key, value)) in members.into_iter().enumerate() {
+                if index != 0 {
+                    w.write_char(',')?;
+                }
+                write_canonical(w, &key.0, options)?;
+                w.write_char(':')?;
+                write_canonical(w, value, options)?;
+            }
+            w.write_char('}')
+        }
+        JsonValue::Array(array) => {
+            w.write_char('[')?;
+            for (index, value) in array.iter().enumerate() {
+                if index != 0 {
+                    w.write_char(',')?;
+                }
+                write_canonical(w, value, options)?;
+            }
+            w.write_char(']')
+        }
+        JsonValue::Number(number) if number.0 == 0.0 => w.write_char('0'),
+        JsonValue::Number(number) => write_number(w, number.0),
+        JsonValue::RawNumber(number) => write_canonical(
+            w,
+            &JsonValue::Number(JsonNumber(number.as_f64(), None)),
+            options,
+        ),
+        _ => write_value(w, value, None, options),
+    }
+}
+
+impl JsonValue {
+    /// Deterministic compact serialization for hashing and comparison.
+    ///
+    /// Object members are sorted by key, comparing Unicode code points, and no
+    /// insignificant whitespace is emitted. Numbers are written the same way
+    /// as `Display` does, as the shortest decimal that round trips to the same
+    /// `f64`, so `1.0`, `1` and `1e0` all become `1`. An exponent is only used
+    /// for magnitudes below `1e-7` or from `1e21` up, always as a lowercase
+    /// `e` without a `+` or leading zeros, like `1e21` and `1.5e-8`. The one
+    /// extra normalization is that `-0` is written as `0`. Raw numbers are
+    /// converted to their nearest `f64` first, so they compare equal to parsed
+    /// ones, and preserved number text is ignored.
+    pub fn to_canonical_string(&self) -> String {
+        let mut out = String::new();
+        write_canonical(&mut out, self, &SerializeOptions::default())
+            .expect("writing to a String can't fail");
+        out
+    }
+
+    /// Compact serialization with the given options
+    pub fn to_string_with_options(&self, options: &SerializeOptions) -> String {
+        let mut out = String::new();
+        write_value(&mut out, self, None, options).expect("writing to a String can't fail");
+        out
+    }
+
+    /// Compact serialization that only emits ASCII, see
+    /// [`SerializeOptions::ascii_only`]
+    pub fn to_string_ascii(&self) -> String {
+        self.to_string_with_options(&SerializeOptions {
+            ascii_only: true,
+            ..SerializeOptions::default()
+        })
+    }
+
+    /// The exact length in bytes of the compact `Display` output, worked out
+    /// without building it
+    pub fn serialized_len(&self) -> usize {
+        let mut counter = ByteCounter(0);
+        write_value(&mut counter, self, None, &SerializeOptions::default())
+            .expect("counting bytes can't fail");
+        counter.0
+    }
+}
+
+/// Adds up the length of everything written to it and discards it
+struct ByteCounter(usize);
+
+impl fmt::Write for ByteCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+/// Returns the nesting level to start from, pretty printing when the
+/// alternate flag (`{:#}`) is set
+fn starting_depth(f: &fmt::Formatter) -> Option<usize> {
+    f.alternate().then_some(0)
+}
+
+/// Compact JSON, or pretty printed JSON with `{:#}`.
+///
+/// Output is lossless with respect to the parsed document, for rewrites that
+/// should produce minimal diffs: members are written in insertion order and
+/// numbers parsed with
+/// [`ParseOptions::preserve_number_text`](crate::ParseOptions::preserve_number_text)
+/// or [`ParseOptions::raw_numbers`](crate::ParseOptions::raw_numbers) as their
+/// source text. Parsing an untouched document that way and writing it back
+/// out compactly gives the original minus insignificant whitespace, as long
+/// as its strings only use the escapes this writes, e.g. `\n` rather than
+/// `\u000a`, and it has no repeated keys.
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_value(f, self, starting_depth(f), &SerializeOptions::default())
+    }
+}
+
+impl fmt::Display for JsonArray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_array(f, self, starting_depth(f), &SerializeOptions::default())
+    }
+}
+
+impl fmt::Display for JsonObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_object(f, self, starting_depth(f), &SerializeOptions::default())
+    }
+}
+
+impl fmt::Display for JsonKeyedObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_members(
+            f,
+            self.iter(),
+            starting_depth(f),
+            &SerializeOptions::default(),
+        )
+    }
+}
+
+impl fmt::Display for JsonObjectSorted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_members(
+            f,
+            self.iter(),
+            starting_depth(f),
+            &SerializeOptions::default(),
+        )
+    }
+}
+
+impl fmt::Display for JsonString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_escaped_str(f, &self.0, &SerializeOptions::default())
+    }
+}
+
+impl fmt::Display for JsonRawNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The source text if it was kept, see
+/// [`ParseOptions::preserve_number_text`](crate::ParseOptions::preserve_number_text)
+impl fmt::Display for JsonNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.source_text() {
+            Some(text) => f.write_str(text),
+            None => write_number(f, self.0),
+        }
+    }
+}
+
+impl fmt::Display for JsonBool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0 { "true" } else { "false" })
+    }
+}
+
+impl fmt::Display for JsonNull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("null")
+    }
+}
+
+/// Most bytes [`IoAdapter`] collects before handing them on
+#[cfg(feature = "std")]
+const WRITE_CHUNK_SIZE: usize = 8 * 1024;
+
+/// Lets the `fmt::Write` based serializer drive an `io::Write`, holding on to
+/// the real I/O error since `fmt::Error` carries no information.
+///
+/// The serializer writes in small pieces, an escaped string alternates
+/// between unescaped runs and escapes, so they're collected into chunks of up
+/// to [`WRITE_CHUNK_SIZE`] bytes. Memory use stays bounded however long the
+/// string, and the inner writer sees a few large writes rather than many tiny
+/// ones. A piece that's a chunk or larger on its own is written directly.
+#[cfg(feature = "std")]
+struct IoAdapter<'a, W: std::io::Write> {
+    inner: &'a mut W,
+    buffer: Vec<u8>,
+    error: Option<std::io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, W: std::io::Write> IoAdapter<'a, W> {
+    fn new(inner: &'a mut W) -> Self {
+        Self {
+            inner,
+            buffer: Vec::with_capacity(WRITE_CHUNK_SIZE),
+            error: None,
+        }
+    }
+
+    fn write_through(&mut self, bytes: &[u8]) -> fmt::Result {
+        self.inner.write_all(bytes).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+
+    /// Hands on whatever has been collected, called once more at the end
+    fn flush_buffer(&mut self) -> fmt::Result {
+        let buffer = core::mem::take(&mut self.buffer);
+        let result = self.write_through(&buffer);
+        self.buffer = buffer;
+        self.buffer.clear();
+        result
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> fmt::Write for IoAdapter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.buffer.len() + s.len() > WRITE_CHUNK_SIZE {
+            self.flush_buffer()?;
+        }
+        if s.len() >= WRITE_CHUNK_SIZE {
+            self.write_through(s.as_bytes())
+        } else {
+            self.buffer.extend_from_slice(s.as_bytes());
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn to_writer_with_depth<W: std::io::Write>(
+    value: &JsonValue,
+    w: &mut W,
+    depth: Option<usize>,
+    options: &SerializeOptions,
+) -> std::io::Result<()> {
+    let mut adapter = IoAdapter::new(w);
+    write_value(&mut adapter, value, depth, options)
+        .and_then(|()| adapter.flush_buffer())
+        .map_err(|_| {
+            adapter
+                .error
+                .take()
+                .unwrap_or_else(|| std::io::Error::other("formatter error"))
+        })
+}
+
+/// Serializes `value` as compact JSON directly into `w`, producing the same
+/// output as `Display`
+#[cfg(feature = "std")]
+pub fn to_writer<W: std::io::Write>(value: &JsonValue, w: &mut W) -> std::io::Result<()> {
+    to_writer_with_depth(value, w, None, &SerializeOptions::default())
+}
+
+/// [`to_writer`] with the given options
+#[cfg(feature = "std")]
+pub fn to_writer_with_options<W: std::io::Write>(
+    value: &JsonValue,
+    w: &mut W,
+    options: &SerializeOptions,
+) -> std::io::Result<()> {
+    to_writer_with_depth(value, w, None, options)
+}
+
+/// Serializes `value` as pretty printed JSON directly into `w`, producing the
+/// same output as `{:#}`
+#[cfg(feature = "std")]
+pub fn to_writer_pretty<W: std::io::Write>(value: &JsonValue, w: &mut W) -> std::io::Result<()> {
+    to_writer_with_depth(value, w, Some(0), &SerializeOptions::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn to_writer_matches_display() {
+        let Ok(value) = parse(r#"{"a": ["x\"y\\z\n\u0001é", 1.5, true, null], "b": {}, "c": []}"#)
+        else {
+            panic!("failed to parse");
+        };
+        let mut compact = Vec::new();
+        to_writer(&value, &mut compact).unwrap();
+        assert_eq!(String::from_utf8(compact).unwrap(), value.to_string());
+        let mut pretty = Vec::new();
+        to_writer_pretty(&value, &mut pretty).unwrap();
+        assert_eq!(String::from_utf8(pretty).unwrap(), format!("{value:#}"));
+    }
+
+    #[test]
+    fn serialized_len_matches_display() {
+        let documents = [
+            r#"{"a": ["x\"y\\z\n\u0001\u0008\u000c", 1.5, -0, 1e300, true, null], "b": {}, "c": []}"#,
+            r#""é 😀 \u2028""#,
+            r#"[[], [[]], {"": ""}]"#,
+            "0.1",
+        ];
+        for document in documents {
+            let value = parse(document).unwrap();
+            assert_eq!(
+                value.serialized_len(),
+                value.to_string().len(),
+                "{document}"
+            );
+        }
+        let preserved = crate::Parser::new()
+            .preserve_number_text(true)
+            .parse("[1.50, 2E3]")
+            .unwrap();
+        assert_eq!(preserved.serialized_len(), preserved.to_string().len());
+    }
+
+    #[test]
+    fn untouched_documents_round_trip_losslessly() {
+        let input = r#"{
+            "zeta": 1.50,
+            "alpha": [1E+2, -0.0, 0.1e-3, 12345678901234567890],
+            "mid": {"b": 10, "a": 2.000, "s": "x y\n\"é\""},
+            "empty": [ ]
+        }"#;
+        let normalized = r#"{"zeta":1.50,"alpha":[1E+2,-0.0,0.1e-3,12345678901234567890],"mid":{"b":10,"a":2.000,"s":"x y\n\"é\""},"empty":[]}"#;
+        for parser in [
+            crate::Parser::new().preserve_number_text(true),
+            crate::Parser::new().raw_numbers(true),
+        ] {
+            let value = parser.parse(input).unwrap();
+            assert_eq!(value.to_string(), normalized);
+        }
+        // Without either option numbers are normalized
+        assert!(parse(input).unwrap().to_string().contains(r#""zeta":1.5,"#));
+    }
+
+    /// Records the size of every write it gets
+    #[cfg(feature = "std")]
+    #[derive(Default)]
+    struct RecordingWriter {
+        written: Vec<u8>,
+        write_sizes: Vec<usize>,
+    }
+
+    #[cfg(feature = "std")]
+    impl std::io::Write for RecordingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.written.extend_from_slice(buf);
+            self.write_sizes.push(buf.len());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn large_strings_are_written_in_chunks() {
+        // Every other character needs escaping, about 4MB of output
+        let unit = "a\"b\n\u{1}é";
+        let string = unit.repeat(300_000);
+        let value = JsonValue::String(JsonString(string));
+        let mut w = RecordingWriter::default();
+        to_writer(&value, &mut w).unwrap();
+
+        let escaped_unit = r#"a\"b\n\u0001é"#;
+        let expected_len = 2 + escaped_unit.len() * 300_000;
+        assert_eq!(w.written.len(), expected_len);
+        let prefix = format!("\"{escaped_unit}{escaped_unit}");
+        assert!(w.written.starts_with(prefix.as_bytes()));
+        let suffix = format!("{escaped_unit}{escaped_unit}\"");
+        assert!(w.written.ends_with(suffix.as_bytes()));
+        assert_eq!(w.written, value.to_string().into_bytes());
+
+        assert!(w.write_sizes.iter().all(|&size| size <= WRITE_CHUNK_SIZE));
+        assert!(w.write_sizes.len() <= expected_len / (WRITE_CHUNK_SIZE / 2) + 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn pretty_output_layout() {
+        let Ok(value) = parse(r#"[1, [], {"a": "\t"}]"#) else {
+            panic!("failed to parse");
+        };
+        let mut pretty = Vec::new();
+        to_writer_pretty(&value, &mut pretty).unwrap();
+        assert_eq!(
+            String::from_utf8(pretty).unwrap(),
+            "[\n  1,\n  [],\n  {\n    \"a\": \"\\t\"\n  }\n]"
+        );
+        assert_eq!(value.to_string(), r#"[1,[],{"a":"\t"}]"#);
+    }
+
+    #[test]
+    fn canonical_string_ignores_key_order() {
+        let Ok(a) = parse(r#"{"b": 1, "a": {"y": [1.0, -0], "x": null}, "é": "", "z": true}"#)
+        else {
+            panic!("failed to parse");
+        };
+        let Ok(b) = parse(r#"{"z": true, "é": "", "a": {"x": null, "y": [1e0, 0]}, "b": 1}"#)
+        else {
+            panic!("failed to parse");
+        };
+        assert_eq!(a.to_canonical_string(), b.to_canonical_string());
+        assert_eq!(
+            a.to_canonical_string(),
+            r#"{"a":{"x":null,"y":[1,0]},"b":1,"z":true,"é":""}"#
+        );
+    }
+
+    #[test]
+    fn ascii_only_round_trip() {
+        let value = JsonValue::from("café 😀\n");
+        let ascii = value.to_string_ascii();
+        assert_eq!(ascii, r#""caf\u00e9 \ud83d\ude00\n""#);
+        assert!(ascii.is_ascii());
+        assert!(matches!(parse(&ascii), Ok(v) if v == value));
+        assert_eq!(value.to_string(), "\"café 😀\\n\"");
+    }
+
+    #[test]
+    fn escape_forward_slash() {
+        let value = json!({"html": "<script>x</script>", "url": "a/b"});
+        assert_eq!(
+            value.to_string(),
+            r#"{"html":"<script>x</script>","url":"a/b"}"#
+        );
+        let options = SerializeOptions {
+            escape_forward_slash: true,
+            ..SerializeOptions::default()
+        };
+        let escaped = value.to_string_with_options(&options);
+        assert_eq!(escaped, r#"{"html":"<script>x<\/script>","url":"a\/b"}"#);
+        #[cfg(feature = "std")]
+        {
+            let mut written = Vec::new();
+            to_writer_with_options(&value, &mut written, &options).unwrap();
+            assert_eq!(String::from_utf8(written).unwrap(), escaped);
+        }
+        assert_eq!(parse(&escaped), Ok(value));
+    }
+
+    #[test]
+    fn numbers_round_trip_exactly() {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let special = [
+            0.0,
+            -0.0,
+            2.0,
+            0.1,
+            1e21,
+            1e-7,
+            9.999e-8,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+        ];
+        let random = (0..20_000).map(|_| f64::from_bits(next()));
+        for n in special.into_iter().chain(random).filter(|n| n.is_finite()) {
+            let text = JsonValue::from(n).to_string();
+            let Ok(JsonValue::Number(parsed)) = parse(&text) else {
+                panic!("{text} didn't parse as a number");
+            };
+            assert_eq!(parsed.0.to_bits(), n.to_bits(), "{text}");
+        }
+    }
+
+    #[test]
+    fn number_formatting() {
+        assert_eq!(JsonValue::from(2.0).to_string(), "2");
+        assert_eq!(JsonValue::from(0.1).to_string(), "0.1");
+        assert_eq!(JsonValue::from(1e300).to_string(), "1e300");
+        assert_eq!(JsonValue::from(-1.5e-8).to_string(), "-1.5e-8");
+        assert_eq!(JsonValue::from(f64::NAN).to_string(), "null");
+    }
+}
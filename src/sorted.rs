@@ -0,0 +1,121 @@
+use crate::{JsonObject, JsonString, JsonValue};
+use alloc::collections::BTreeMap;
+
+/// An object whose members are always in key order, for consumers that want
+/// sorted iteration and serialization without a canonicalization step.
+///
+/// Parse with [`Parser`](crate::Parser) as usual and convert the resulting
+/// [`JsonObject`], so duplicate keys are handled by the parser's
+/// `duplicate_key_policy` before sorting. Nested objects are sorted by the
+/// conversion too, they stay [`JsonObject`]s with their members reordered.
+#[derive(Debug, Default, PartialEq, Eq, Hash)]
+pub struct JsonObjectSorted(pub(crate) BTreeMap<JsonString, JsonValue>);
+
+impl JsonObjectSorted {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        self.0.get(key)
+    }
+
+    /// Objects nested in `value` are sorted, like in the conversion
+    pub fn insert(&mut self, key: JsonString, mut value: JsonValue) -> Option<JsonValue> {
+        sort_nested(&mut value);
+        self.0.insert(key, value)
+    }
+
+    /// Members in key order
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&JsonString, &JsonValue)> {
+        self.0.iter()
+    }
+}
+
+impl From<JsonObject> for JsonObjectSorted {
+    fn from(object: JsonObject) -> Self {
+        Self(
+            object
+                .into_iter()
+                .map(|(key, mut value)| {
+                    sort_nested(&mut value);
+                    (key, value)
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Reorders the members of every object in `value` by key. Keyed objects
+/// keep their order, since their keys aren't ordered, but what they contain
+/// is still sorted.
+fn sort_nested(value: &mut JsonValue) {
+    match value {
+        JsonValue::Object(object) => {
+            object.0.sort_by(|(a, _), (b, _)| a.cmp(b));
+            object.values_mut().for_each(sort_nested);
+        }
+        JsonValue::KeyedObject(object) => object.0.iter_mut().for_each(|(_, v)| sort_nested(v)),
+        JsonValue::Array(array) => array.0.iter_mut().for_each(sort_nested),
+        _ => {}
+    }
+}
+
+impl From<JsonObjectSorted> for JsonObject {
+    fn from(sorted: JsonObjectSorted) -> Self {
+        JsonObject(sorted.0.into_iter().collect())
+    }
+}
+
+impl IntoIterator for JsonObjectSorted {
+    type Item = (JsonString, JsonValue);
+    type IntoIter = alloc::collections::btree_map::IntoIter<JsonString, JsonValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn iterates_in_key_order() {
+        let Ok(JsonValue::Object(object)) = parse(r#"{"z":1,"a":2}"#) else {
+            panic!("expected an object");
+        };
+        let sorted = JsonObjectSorted::from(object);
+        let keys: Vec<&str> = sorted.iter().map(|(k, _)| k.0.as_str()).collect();
+        assert_eq!(keys, ["a", "z"]);
+        assert_eq!(sorted.to_string(), r#"{"a":2,"z":1}"#);
+    }
+
+    #[test]
+    fn nested_objects_are_sorted() {
+        let Ok(JsonValue::Object(object)) = parse(r#"{"z":{"y":1,"x":[{"b":2,"a":3}]},"a":2}"#)
+        else {
+            panic!("expected an object");
+        };
+        let sorted = JsonObjectSorted::from(object);
+        assert_eq!(
+            sorted.to_string(),
+            r#"{"a":2,"z":{"x":[{"a":3,"b":2}],"y":1}}"#
+        );
+    }
+
+    #[test]
+    fn duplicate_keys_follow_parser_setting() {
+        let Ok(JsonValue::Object(object)) = parse(r#"{"b":1,"a":2,"b":3}"#) else {
+            panic!("expected an object");
+        };
+        let sorted = JsonObjectSorted::from(object);
+        assert_eq!(sorted.len(), 2);
+        assert_eq!(sorted.get("b"), Some(&json!(3)));
+    }
+}
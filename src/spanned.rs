@@ -0,0 +1,199 @@
+use crate::members::{insert_member, KeyIndex, Member};
+use crate::{
+    parse_array_with, parse_object_with, CharIterator, DuplicateKeyPolicy, JsonArray, JsonObject,
+    JsonString, JsonType, JsonValue, ParseError, Parser, Span, WhitespaceSkippingIndexTrackingIter,
+};
+use alloc::vec::Vec;
+
+/// A value along with the part of the input it was parsed from
+#[derive(Debug, PartialEq)]
+pub struct Spanned<T> {
+    pub value: T,
+    /// From the first character of the value to just past its last, so
+    /// surrounding whitespace isn't included
+    pub span: Span,
+}
+
+/// An object member, key and value each with their own span
+pub type SpannedMember = (Spanned<JsonString>, Spanned<JsonValueSpanned>);
+
+/// A [`JsonValue`] where every value and key carries its [`Span`], see
+/// [`Parser::parse_spanned`].
+///
+/// Objects keep the same semantics as [`JsonObject`]: insertion order, and a
+/// repeated key replaces the earlier member in place, spans included. Keys
+/// are always strings,
+/// [`ParseOptions::allow_non_string_keys`](crate::ParseOptions::allow_non_string_keys)
+/// isn't supported.
+#[derive(Debug, PartialEq)]
+pub enum JsonValueSpanned {
+    Object(Vec<SpannedMember>),
+    Array(Vec<Spanned<JsonValueSpanned>>),
+    /// Strings, numbers, bools and null, exactly as [`Parser::parse`] would
+    /// have parsed them
+    Scalar(JsonValue),
+}
+
+impl JsonValueSpanned {
+    /// Drops the spans
+    pub fn into_value(self) -> JsonValue {
+        match self {
+            // Parsing already made the keys unique
+            JsonValueSpanned::Object(members) => JsonValue::Object(JsonObject(
+                members
+                    .into_iter()
+                    .map(|(key, value)| (key.value, value.value.into_value()))
+                    .collect(),
+            )),
+            JsonValueSpanned::Array(elements) => JsonValue::Array(JsonArray(
+                elements
+                    .into_iter()
+                    .map(|element| element.value.into_value())
+                    .collect(),
+            )),
+            JsonValueSpanned::Scalar(value) => value,
+        }
+    }
+}
+
+impl Parser {
+    /// Same as [`Parser::parse`], but recording where in `input` each value
+    /// and key came from, for editors and linters. Spans count `char`s, like
+    /// error indices.
+    pub fn parse_spanned(&self, input: &str) -> Result<Spanned<JsonValueSpanned>, ParseError> {
+        self.parse_document_with(input.chars(), parse_value)
+    }
+}
+
+/// [`Parser::parse_spanned`] with the default [`Parser`]
+pub fn parse_spanned(input: &str) -> Result<Spanned<JsonValueSpanned>, ParseError> {
+    Parser::new().parse_spanned(input)
+}
+
+/// Runs `parse` and spans what it consumed, whitespace having been skipped
+fn spanned<CI: CharIterator, T>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+    parse: impl FnOnce(&mut WhitespaceSkippingIndexTrackingIter<CI>) -> Result<T, ParseError>,
+) -> Result<Spanned<T>, ParseError> {
+    let start = i.next_char_index();
+    let value = parse(i)?;
+    Ok(Spanned {
+        value,
+        span: Span {
+            start,
+            end: i.next_char_index(),
+        },
+    })
+}
+
+fn parse_value<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+) -> Result<Spanned<JsonValueSpanned>, ParseError> {
+    let next_char = i
+        .peek_non_whitespace()?
+        .ok_or(ParseError::UnexpectedEndOfString)?;
+    spanned(i, |i| {
+        // Scalars are counted by `JsonValue::parse`
+        if matches!(next_char, '{' | '[') {
+            i.count_element()?;
+        }
+        match next_char {
+            '{' => parse_object(i).map(JsonValueSpanned::Object),
+            '[' => parse_array_with(i, parse_value).map(JsonValueSpanned::Array),
+            _ => JsonValue::parse(i).map(JsonValueSpanned::Scalar),
+        }
+    })
+}
+
+fn parse_object<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+) -> Result<Vec<SpannedMember>, ParseError> {
+    let mut members: Vec<SpannedMember> = Vec::new();
+    let mut index = KeyIndex::default();
+    parse_object_with(i, |i| {
+        let key_byte_index = i.next_byte_index;
+        let key = spanned(i, JsonString::parse_key)?;
+        let policy = i.options.duplicate_key_policy;
+        if policy == DuplicateKeyPolicy::Error && index.position(&members, &key.value).is_some() {
+            return Err(ParseError::DuplicateKey {
+                key: key.value.0,
+                char_index: key.span.start,
+                byte_index: key_byte_index,
+            });
+        }
+        i.expect_specific_char_ignore_whitespace(':')?;
+        let value = parse_value(i)?;
+        insert_member(&mut members, &mut index, (key, value), policy);
+        Ok(())
+    })?;
+    Ok(members)
+}
+
+/// A merged object keeps the span of its first occurrence, a replaced
+/// member takes the spans of the one replacing it
+impl Member for SpannedMember {
+    type Key = JsonString;
+
+    fn key(&self) -> &JsonString {
+        &self.0.value
+    }
+
+    fn object_members(&mut self) -> Option<&mut Vec<Self>> {
+        match &mut self.1.value {
+            JsonValueSpanned::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agreement::{assert_agrees_with_parse, parsers};
+
+    /// The characters `span` covers
+    fn slice(input: &str, span: Span) -> String {
+        input
+            .chars()
+            .skip(span.start)
+            .take(span.end - span.start)
+            .collect()
+    }
+
+    #[test]
+    fn spans_match_the_source() {
+        let input = "{\"é\": [1.50, {\"key\" : \"v\\n\"} ], \"n\":null }";
+        let root = parse_spanned(input).unwrap();
+        assert_eq!(slice(input, root.span), input.trim_end());
+        let JsonValueSpanned::Object(members) = &root.value else {
+            panic!("expected an object");
+        };
+        let (key, array) = &members[0];
+        assert_eq!(slice(input, key.span), "\"é\"");
+        assert_eq!(slice(input, array.span), "[1.50, {\"key\" : \"v\\n\"} ]");
+        let JsonValueSpanned::Array(elements) = &array.value else {
+            panic!("expected an array");
+        };
+        assert_eq!(slice(input, elements[0].span), "1.50");
+        assert_eq!(slice(input, elements[1].span), "{\"key\" : \"v\\n\"}");
+        let JsonValueSpanned::Object(inner) = &elements[1].value else {
+            panic!("expected an object");
+        };
+        assert_eq!(slice(input, inner[0].1.span), "\"v\\n\"");
+        assert_eq!(inner[0].1.value, JsonValueSpanned::Scalar(json!("v\n")));
+        assert_eq!(slice(input, members[1].1.span), "null");
+    }
+
+    #[test]
+    fn agrees_with_parse() {
+        assert_agrees_with_parse(
+            &parsers(),
+            |parser, input| {
+                parser
+                    .parse_spanned(input)
+                    .map(|root| root.value.into_value())
+            },
+            |value| value,
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use crate::keyed::JsonKeyedObject;
+use crate::visit::Visitor;
+use crate::{JsonArray, JsonBool, JsonNumber, JsonObject, JsonRawNumber, JsonString, JsonValue};
+
+/// Counts of what a document is made of, see [`JsonValue::stats`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DocumentStats {
+    pub objects: usize,
+    pub arrays: usize,
+    pub strings: usize,
+    /// Includes [`JsonValue::RawNumber`]s
+    pub numbers: usize,
+    pub bools: usize,
+    pub nulls: usize,
+    /// Members across every object, counting repeats of the same key
+    pub keys: usize,
+    /// Deepest nesting of arrays and objects, 0 for a lone scalar
+    pub max_depth: usize,
+}
+
+/// Builds [`DocumentStats`] while tracking the current depth
+#[derive(Default)]
+struct StatsVisitor {
+    stats: DocumentStats,
+    depth: usize,
+}
+
+impl StatsVisitor {
+    fn enter(&mut self) {
+        self.depth += 1;
+        self.stats.max_depth = self.stats.max_depth.max(self.depth);
+    }
+}
+
+impl Visitor for StatsVisitor {
+    fn visit_object_start(&mut self, _: &JsonObject) {
+        self.stats.objects += 1;
+        self.enter();
+    }
+
+    fn visit_object_key(&mut self, _: &JsonString) {
+        self.stats.keys += 1;
+    }
+
+    fn visit_object_end(&mut self, _: &JsonObject) {
+        self.depth -= 1;
+    }
+
+    fn visit_keyed_object_start(&mut self, _: &JsonKeyedObject) {
+        self.stats.objects += 1;
+        self.enter();
+    }
+
+    fn visit_non_string_key(&mut self, _: &JsonValue) {
+        self.stats.keys += 1;
+    }
+
+    fn visit_keyed_object_end(&mut self, _: &JsonKeyedObject) {
+        self.depth -= 1;
+    }
+
+    fn visit_array_start(&mut self, _: &JsonArray) {
+        self.stats.arrays += 1;
+        self.enter();
+    }
+
+    fn visit_array_end(&mut self, _: &JsonArray) {
+        self.depth -= 1;
+    }
+
+    fn visit_string(&mut self, _: &JsonString) {
+        self.stats.strings += 1;
+    }
+
+    fn visit_number(&mut self, _: &JsonNumber) {
+        self.stats.numbers += 1;
+    }
+
+    fn visit_raw_number(&mut self, _: &JsonRawNumber) {
+        self.stats.numbers += 1;
+    }
+
+    fn visit_bool(&mut self, _: &JsonBool) {
+        self.stats.bools += 1;
+    }
+
+    fn visit_null(&mut self) {
+        self.stats.nulls += 1;
+    }
+}
+
+impl JsonValue {
+    /// Counts the values of each kind in the tree, including `self`
+    pub fn stats(&self) -> DocumentStats {
+        let mut visitor = StatsVisitor::default();
+        self.accept(&mut visitor);
+        visitor.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_nested_document() {
+        let value = json!({
+            "a": [1, 2.5, {"b": [[]], "c": null}],
+            "d": "e",
+            "f": {"g": true, "h": false},
+            "i": []
+        });
+        assert_eq!(
+            value.stats(),
+            DocumentStats {
+                objects: 3,
+                arrays: 4,
+                strings: 1,
+                numbers: 2,
+                bools: 2,
+                nulls: 1,
+                keys: 8,
+                max_depth: 5,
+            }
+        );
+        assert_eq!(
+            json!("x").stats(),
+            DocumentStats {
+                strings: 1,
+                ..DocumentStats::default()
+            }
+        );
+        let raw = crate::Parser::new().raw_numbers(true).parse("[1]").unwrap();
+        assert_eq!(raw.stats().numbers, 1);
+        let keyed = crate::Parser::new()
+            .allow_non_string_keys(true)
+            .parse(r#"{1: {"a": 2}}"#)
+            .unwrap();
+        assert_eq!(
+            keyed.stats(),
+            DocumentStats {
+                objects: 2,
+                numbers: 1,
+                keys: 2,
+                max_depth: 2,
+                ..DocumentStats::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{
+    start_document, CharIterator, JsonType, JsonValue, ParseError, Parser,
+    WhitespaceSkippingIndexTrackingIter,
+};
+use alloc::vec;
+
+/// Yields the elements of a top-level array one at a time, see
+/// [`Parser::parse_array_stream`]
+struct ArrayStream<CI: CharIterator> {
+    i: WhitespaceSkippingIndexTrackingIter<CI>,
+    /// Whether the opening `[` has been consumed
+    started: bool,
+    /// Set once the array is closed or an error was yielded
+    done: bool,
+}
+
+impl<CI: CharIterator> ArrayStream<CI> {
+    /// Parses up to and including the next element, `None` once the array
+    /// and the document are over
+    fn advance(&mut self) -> Result<Option<JsonValue>, ParseError> {
+        let i = &mut self.i;
+        if !self.started {
+            self.started = true;
+            start_document(i)?;
+            i.count_element()?;
+            i.expect_specific_char_ignore_whitespace('[')?;
+            i.enter_nesting()?;
+            if i.next_non_whitespace_if_eq(']')?.is_some() {
+                return self.finish();
+            }
+        } else {
+            let next_char = i
+                .next_non_whitespace()?
+                .ok_or(ParseError::UnexpectedEndOfString)?;
+            if next_char == ']' {
+                return self.finish();
+            } else if next_char != ',' {
+                return Err(i.unexpected_character(next_char, vec![']', ',']));
+            } else if i.options.allow_trailing_commas && i.next_non_whitespace_if_eq(']')?.is_some()
+            {
+                return self.finish();
+            }
+        }
+        JsonValue::parse(&mut self.i).map(Some)
+    }
+
+    /// Called after the closing `]`, only whitespace may follow
+    fn finish(&mut self) -> Result<Option<JsonValue>, ParseError> {
+        let i = &mut self.i;
+        i.exit_nesting();
+        match i.next_non_whitespace()? {
+            Some(c) => Err(ParseError::TrailingCharacter {
+                character: c,
+                char_index: i.previous_char_index(),
+                byte_index: i.previous_byte_index(),
+            }),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<CI: CharIterator> Iterator for ArrayStream<CI> {
+    type Item = Result<JsonValue, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.advance().transpose();
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+        result
+    }
+}
+
+impl Parser {
+    /// Parses a document that is a single array, yielding each element as
+    /// soon as it's parsed instead of collecting them. Errors, including
+    /// anything after the closing `]`, are yielded as the last item.
+    pub fn parse_array_stream<'a>(
+        &self,
+        input: &'a str,
+    ) -> impl Iterator<Item = Result<JsonValue, ParseError>> + 'a {
+        ArrayStream {
+            i: WhitespaceSkippingIndexTrackingIter::with_options(
+                input.chars(),
+                self.options.clone(),
+            ),
+            started: false,
+            done: false,
+        }
+    }
+}
+
+/// [`Parser::parse_array_stream`] with the default [`Parser`]
+pub fn parse_array_stream(input: &str) -> impl Iterator<Item = Result<JsonValue, ParseError>> + '_ {
+    Parser::new().parse_array_stream(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Span;
+
+    #[test]
+    fn yields_elements_in_order() {
+        let elements: Vec<_> = parse_array_stream(r#" [1, "two", [3], {"four": 4}] "#).collect();
+        assert_eq!(
+            elements,
+            [
+                Ok(json!(1)),
+                Ok(json!("two")),
+                Ok(json!([3])),
+                Ok(json!({"four": 4})),
+            ]
+        );
+        assert_eq!(parse_array_stream("[]").count(), 0);
+        let elements: Vec<_> = Parser::new()
+            .allow_trailing_commas(true)
+            .parse_array_stream("[true, null,]")
+            .collect();
+        assert_eq!(elements, [Ok(json!(true)), Ok(json!(null))]);
+    }
+
+    #[test]
+    fn errors_end_the_stream() {
+        let mut stream = parse_array_stream("[1, 2] x");
+        assert_eq!(stream.next(), Some(Ok(json!(1))));
+        assert_eq!(stream.next(), Some(Ok(json!(2))));
+        assert_eq!(
+            stream.next(),
+            Some(Err(ParseError::TrailingCharacter {
+                character: 'x',
+                char_index: 7,
+                byte_index: 7
+            }))
+        );
+        assert_eq!(stream.next(), None);
+
+        let elements: Vec<_> = parse_array_stream("[1, tru, 3]").collect();
+        assert_eq!(
+            elements,
+            [
+                Ok(json!(1)),
+                Err(ParseError::UnexpectedCharacter {
+                    character: ',',
+                    char_index: 7,
+                    byte_index: 7,
+                    expected_characters: vec!['e'],
+                    span: Span::single(7)
+                })
+            ]
+        );
+        assert_eq!(
+            parse_array_stream("{}").collect::<Vec<_>>(),
+            [Err(ParseError::UnexpectedCharacter {
+                character: '{',
+                char_index: 0,
+                byte_index: 0,
+                expected_characters: vec!['['],
+                span: Span::single(0)
+            })]
+        );
+        assert_eq!(
+            parse_array_stream("[1 2]").collect::<Vec<_>>(),
+            [
+                Ok(json!(1)),
+                Err(ParseError::UnexpectedCharacter {
+                    character: '2',
+                    char_index: 3,
+                    byte_index: 3,
+                    expected_characters: vec![']', ','],
+                    span: Span::single(3)
+                })
+            ]
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use crate::keyed::JsonKeyedObject;
+use crate::{
+    check_precision, expect_key_start, next_key_is_unquoted, parse_array_with, parse_identifier,
+    parse_number_token, parse_object_with, unexpected_value_start, CharIterator,
+    DuplicateKeyPolicy, JsonBool, JsonNull, JsonString, JsonType, ParseError, Parser,
+    WhitespaceSkippingIndexTrackingIter,
+};
+use alloc::collections::BTreeSet;
+
+impl Parser {
+    /// Checks that `input` is a document [`Parser::parse`] would accept,
+    /// returning the same error it would, without building the value.
+    /// Strings and numbers are scanned into a reused buffer, so nothing is
+    /// allocated per value. Keys are only kept with
+    /// [`DuplicateKeyPolicy::Error`], to detect repeats.
+    pub fn validate(&self, input: &str) -> Result<(), ParseError> {
+        self.parse_document_with(input.chars(), validate_value)
+    }
+}
+
+/// [`Parser::validate`] with the default [`Parser`]
+pub fn validate(input: &str) -> Result<(), ParseError> {
+    Parser::new().validate(input)
+}
+
+fn validate_value<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+) -> Result<(), ParseError> {
+    let next_char = i
+        .peek_non_whitespace()?
+        .ok_or(ParseError::UnexpectedEndOfString)?;
+    i.count_element()?;
+    match next_char {
+        // Rare enough that building the keys to compare them isn't worth
+        // avoiding
+        '{' if i.options.allow_non_string_keys => JsonKeyedObject::parse(i).map(drop),
+        '{' => validate_object(i),
+        // A `Vec<()>` never allocates
+        '[' => parse_array_with(i, validate_value).map(drop),
+        '"' => validate_string(i),
+        '\'' if i.options.allow_single_quotes => validate_string(i),
+        c if i.options.starts_number(c) => {
+            let mut text = i.take_scratch();
+            parse_number_token(i, &mut text)?;
+            if i.options.reject_imprecise_numbers && !i.options.raw_numbers {
+                check_precision(i, &text, text.parse().unwrap())?;
+            }
+            i.return_scratch(text);
+            Ok(())
+        }
+        't' | 'f' => JsonBool::parse(i).map(drop),
+        'n' => JsonNull::parse(i).map(drop),
+        _ => Err(unexpected_value_start(i)),
+    }
+}
+
+fn validate_object<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+) -> Result<(), ParseError> {
+    let mut keys = BTreeSet::new();
+    parse_object_with(i, |i| {
+        if i.options.duplicate_key_policy != DuplicateKeyPolicy::Error {
+            validate_key(i)?;
+        } else {
+            let key_char_index = i.next_char_index();
+            let key_byte_index = i.next_byte_index;
+            let key = JsonString::parse_key(i)?;
+            if !keys.insert(key.0.clone()) {
+                return Err(ParseError::DuplicateKey {
+                    key: key.0,
+                    char_index: key_char_index,
+                    byte_index: key_byte_index,
+                });
+            }
+        }
+        i.expect_specific_char_ignore_whitespace(':')?;
+        validate_value(i)
+    })
+}
+
+fn validate_key<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+) -> Result<(), ParseError> {
+    expect_key_start(i)?;
+    if next_key_is_unquoted(i)? {
+        return parse_identifier(i, |_| {});
+    }
+    validate_string(i)
+}
+
+fn validate_string<CI: CharIterator>(
+    i: &mut WhitespaceSkippingIndexTrackingIter<CI>,
+) -> Result<(), ParseError> {
+    let quote = i.expect_opening_quote()?;
+    let mut string = i.take_scratch();
+    JsonString::parse_rest(i, &mut string, quote)?;
+    i.return_scratch(string);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agreement::{assert_agrees_with_parse, parsers};
+
+    #[test]
+    fn agrees_with_parse() {
+        let mut parsers = parsers();
+        parsers.push(Parser::new().allow_non_string_keys(true));
+        assert_agrees_with_parse(&parsers, Parser::validate, drop);
+        assert_eq!(validate("[1]"), Ok(()));
+    }
+}
@@ -0,0 +1,105 @@
+use crate::keyed::JsonKeyedObject;
+use crate::{JsonArray, JsonBool, JsonNumber, JsonObject, JsonRawNumber, JsonString, JsonValue};
+
+/// Callbacks for a depth-first walk over a borrowed [`JsonValue`], driven by
+/// [`JsonValue::accept`]. Every method does nothing by default, so visitors
+/// only implement what they care about.
+///
+/// Object members are reported as `visit_object_key` followed by the visit of
+/// the member's value. Keys are not reported through `visit_string`. Members
+/// of a [`JsonKeyedObject`] are reported the same way, except that keys which
+/// aren't strings go to `visit_non_string_key`.
+pub trait Visitor {
+    fn visit_object_start(&mut self, _object: &JsonObject) {}
+    fn visit_object_key(&mut self, _key: &JsonString) {}
+    fn visit_object_end(&mut self, _object: &JsonObject) {}
+    fn visit_keyed_object_start(&mut self, _object: &JsonKeyedObject) {}
+    fn visit_non_string_key(&mut self, _key: &JsonValue) {}
+    fn visit_keyed_object_end(&mut self, _object: &JsonKeyedObject) {}
+    fn visit_array_start(&mut self, _array: &JsonArray) {}
+    fn visit_array_end(&mut self, _array: &JsonArray) {}
+    fn visit_string(&mut self, _string: &JsonString) {}
+    fn visit_number(&mut self, _number: &JsonNumber) {}
+    fn visit_raw_number(&mut self, _number: &JsonRawNumber) {}
+    fn visit_bool(&mut self, _b: &JsonBool) {}
+    fn visit_null(&mut self) {}
+}
+
+impl JsonValue {
+    /// Walks the tree depth-first, calling into `visitor` for each value
+    pub fn accept<V: Visitor>(&self, visitor: &mut V) {
+        match self {
+            JsonValue::Object(object) => {
+                visitor.visit_object_start(object);
+                for (key, value) in object.iter() {
+                    visitor.visit_object_key(key);
+                    value.accept(visitor);
+                }
+                visitor.visit_object_end(object);
+            }
+            JsonValue::KeyedObject(object) => {
+                visitor.visit_keyed_object_start(object);
+                for (key, value) in object.iter() {
+                    match &key.0 {
+                        JsonValue::String(key) => visitor.visit_object_key(key),
+                        key => visitor.visit_non_string_key(key),
+                    }
+                    value.accept(visitor);
+                }
+                visitor.visit_keyed_object_end(object);
+            }
+            JsonValue::Array(array) => {
+                visitor.visit_array_start(array);
+                for value in array {
+                    value.accept(visitor);
+                }
+                visitor.visit_array_end(array);
+            }
+            JsonValue::String(string) => visitor.visit_string(string),
+            JsonValue::Number(number) => visitor.visit_number(number),
+            JsonValue::RawNumber(number) => visitor.visit_raw_number(number),
+            JsonValue::Bool(b) => visitor.visit_bool(b),
+            JsonValue::Null(_) => visitor.visit_null(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Counter {
+        strings: usize,
+        numbers: usize,
+        keys: Vec<String>,
+    }
+
+    impl Visitor for Counter {
+        fn visit_object_key(&mut self, key: &JsonString) {
+            self.keys.push(key.0.clone());
+        }
+
+        fn visit_string(&mut self, _: &JsonString) {
+            self.strings += 1;
+        }
+
+        fn visit_number(&mut self, _: &JsonNumber) {
+            self.numbers += 1;
+        }
+    }
+
+    #[test]
+    fn counting_visitor() {
+        let Ok(value) =
+            crate::parse(r#"{"a": ["x", 1, {"b": 2.5, "c": "y"}], "d": null, "e": "z"}"#)
+        else {
+            panic!("failed to parse");
+        };
+        let mut counter = Counter::default();
+        value.accept(&mut counter);
+        assert_eq!(counter.strings, 3);
+        assert_eq!(counter.numbers, 2);
+        assert_eq!(counter.keys, ["a", "b", "c", "d", "e"]);
+    }
+}